@@ -1,4 +1,22 @@
-use std::{env, fs};
+use std::fs;
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::planner::CrewPlanner;
+
+mod planner;
+
+#[derive(Parser, Debug, Eq, PartialEq, Clone)]
+struct Cli {
+    /// Section range to cover with the smallest crew, for example 2-8
+    #[arg(short, long)]
+    cover: Option<String>,
+
+    /// File to parse
+    #[arg(default_value = "input")]
+    path: PathBuf,
+}
 
 #[derive(Debug)]
 struct Assignment {
@@ -24,20 +42,22 @@ impl AssignmentPair {
     }
 }
 
+impl<'a> From<&'a str> for Assignment {
+    fn from(value: &'a str) -> Self {
+        let (start, end) = value.split_once('-').unwrap();
+        Self {
+            start: start.parse().unwrap(),
+            end: end.parse().unwrap(),
+        }
+    }
+}
+
 impl<'a> From<&'a str> for AssignmentPair {
     fn from(value: &'a str) -> Self {
         let (left, right) = value.split_once(',').unwrap();
-        let (l1, l2) = left.split_once('-').unwrap();
-        let (r1, r2) = right.split_once('-').unwrap();
         Self {
-            left: Assignment {
-                start: l1.parse().unwrap(),
-                end: l2.parse().unwrap(),
-            },
-            right: Assignment {
-                start: r1.parse().unwrap(),
-                end: r2.parse().unwrap(),
-            },
+            left: Assignment::from(left),
+            right: Assignment::from(right),
         }
     }
 }
@@ -62,17 +82,37 @@ fn solve_part_two(data: &str) {
     println!("Part one solution: {:#?}", res);
 }
 
-fn main() {
-    let args: Vec<_> = env::args().collect();
+fn plan_crew(data: &str, cover: Option<&str>) {
+    let assignments: Vec<_> = data
+        .lines()
+        .flat_map(|line| {
+            let pair = AssignmentPair::from(line);
+            [pair.left, pair.right]
+        })
+        .collect();
+    let planner = CrewPlanner::new(&assignments);
+
+    if let Some(cover) = cover {
+        let target = Assignment::from(cover);
+        match planner.minimal_crew(&target) {
+            Ok(crew) => println!("Minimal crew covering {}: {:?}", cover, crew),
+            Err(gaps) => {
+                for gap in gaps {
+                    println!("Sections {}-{} are not covered", gap.start, gap.end);
+                }
+            }
+        }
+    }
 
-    let file_path = if args.len() > 1 {
-        args[1].clone()
-    } else {
-        "input".to_string()
-    };
+    println!("Redundant elves: {:?}", planner.redundant_elves());
+}
+
+fn main() {
+    let args = Cli::parse();
 
-    let data = fs::read_to_string(&file_path).unwrap();
+    let data = fs::read_to_string(&args.path).unwrap();
 
     solve_part_one(&data);
     solve_part_two(&data);
+    plan_crew(&data, args.cover.as_deref());
 }
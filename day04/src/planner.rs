@@ -0,0 +1,171 @@
+use crate::Assignment;
+
+/// A range of sections that no elf is assigned to.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Gap {
+    pub start: i32,
+    pub end: i32,
+}
+
+/// Elves are identified by their index in the flattened assignment list: the elves of the pair
+/// on line `n` (starting from 0) are `2 * n` and `2 * n + 1`.
+pub struct CrewPlanner<'a> {
+    assignments: &'a [Assignment],
+}
+
+impl<'a> CrewPlanner<'a> {
+    pub fn new(assignments: &'a [Assignment]) -> Self {
+        Self { assignments }
+    }
+
+    /// Find the smallest set of elves whose assignments cover every section of the target range.
+    /// Return every uncovered range if this is not possible.
+    pub fn minimal_crew(&self, target: &Assignment) -> Result<Vec<usize>, Vec<Gap>> {
+        minimal_cover(self.assignments.iter().enumerate(), target)
+    }
+
+    /// Find the elves whose assignment is fully covered by the union of the other assignments.
+    pub fn redundant_elves(&self) -> Vec<usize> {
+        (0..self.assignments.len())
+            .filter(|&elf| {
+                minimal_cover(
+                    self.assignments
+                        .iter()
+                        .enumerate()
+                        .filter(|(id, _)| *id != elf),
+                    &self.assignments[elf],
+                )
+                .is_ok()
+            })
+            .collect()
+    }
+}
+
+/// Greedy interval cover: while the target is not fully covered, take the assignment that starts
+/// before the first uncovered section and reaches the furthest.
+fn minimal_cover<'a>(
+    assignments: impl Iterator<Item = (usize, &'a Assignment)>,
+    target: &Assignment,
+) -> Result<Vec<usize>, Vec<Gap>> {
+    let mut candidates: Vec<_> = assignments
+        .filter(|(_, a)| a.end >= target.start && a.start <= target.end)
+        .collect();
+    candidates.sort_by_key(|(_, a)| a.start);
+
+    let mut crew = vec![];
+    let mut gaps = vec![];
+
+    let mut current = target.start;
+    let mut index = 0;
+    while current <= target.end {
+        let mut best: Option<(usize, i32)> = None;
+        while index < candidates.len() && candidates[index].1.start <= current {
+            let (id, assignment) = candidates[index];
+            if best.is_none_or(|(_, end)| assignment.end > end) {
+                best = Some((id, assignment.end));
+            }
+            index += 1;
+        }
+
+        match best {
+            Some((id, end)) if end >= current => {
+                crew.push(id);
+                current = end + 1;
+            }
+            _ => {
+                // Skip to the next assignment starting after the uncovered section
+                let next = candidates
+                    .get(index)
+                    .map_or(target.end + 1, |(_, a)| a.start.min(target.end + 1));
+                gaps.push(Gap {
+                    start: current,
+                    end: next - 1,
+                });
+                current = next;
+            }
+        }
+    }
+
+    if gaps.is_empty() {
+        Ok(crew)
+    } else {
+        Err(gaps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assignments(ranges: &[(i32, i32)]) -> Vec<Assignment> {
+        ranges
+            .iter()
+            .map(|&(start, end)| Assignment { start, end })
+            .collect()
+    }
+
+    #[test]
+    fn test_minimal_crew_full_cover() {
+        let assignments = assignments(&[(2, 4), (1, 3), (3, 7), (5, 9), (6, 6)]);
+        let planner = CrewPlanner::new(&assignments);
+
+        let mut crew = planner
+            .minimal_crew(&Assignment { start: 1, end: 9 })
+            .unwrap();
+        crew.sort_unstable();
+        assert_eq!(crew, vec![1, 2, 3]);
+
+        // The assignment reaching the furthest is chosen
+        assert_eq!(
+            planner.minimal_crew(&Assignment { start: 6, end: 6 }),
+            Ok(vec![3])
+        );
+    }
+
+    #[test]
+    fn test_minimal_crew_gaps() {
+        let assignments = assignments(&[(1, 2), (5, 6), (9, 10)]);
+        let planner = CrewPlanner::new(&assignments);
+
+        assert_eq!(
+            planner.minimal_crew(&Assignment { start: 1, end: 10 }),
+            Err(vec![Gap { start: 3, end: 4 }, Gap { start: 7, end: 8 }])
+        );
+        // The target starts before every assignment
+        assert_eq!(
+            planner.minimal_crew(&Assignment { start: 0, end: 6 }),
+            Err(vec![Gap { start: 0, end: 0 }, Gap { start: 3, end: 4 }])
+        );
+    }
+
+    #[test]
+    fn test_minimal_crew_past_every_assignment() {
+        let assignments = assignments(&[(1, 4), (3, 6)]);
+        let planner = CrewPlanner::new(&assignments);
+
+        assert_eq!(
+            planner.minimal_crew(&Assignment { start: 2, end: 9 }),
+            Err(vec![Gap { start: 7, end: 9 }])
+        );
+        assert_eq!(
+            planner.minimal_crew(&Assignment { start: 8, end: 9 }),
+            Err(vec![Gap { start: 8, end: 9 }])
+        );
+    }
+
+    #[test]
+    fn test_redundant_elves() {
+        let assignments = assignments(&[(1, 5), (2, 3), (4, 8), (6, 8), (10, 12)]);
+        let planner = CrewPlanner::new(&assignments);
+        // The third assignment is covered by the first and the fourth ones
+        assert_eq!(planner.redundant_elves(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_redundant_duplicates() {
+        // Each copy is covered by the other one
+        let assignments = assignments(&[(3, 7), (3, 7), (8, 9)]);
+        let planner = CrewPlanner::new(&assignments);
+        assert_eq!(planner.redundant_elves(), vec![0, 1]);
+    }
+}
@@ -0,0 +1,61 @@
+use std::num::NonZeroUsize;
+
+/// A crane moves crates between stacks, possibly several at once.
+pub trait Crane {
+    /// Maximum number of crates lifted at once, `None` if the crane can lift any number of crates
+    fn max_batch(&self) -> Option<NonZeroUsize>;
+
+    /// Move `quantity` crates from the `src` stack to the `dst` stack. Crates lifted together keep
+    /// their order. The caller must make sure that the source stack holds enough crates.
+    fn move_crates(&self, stacks: &mut [Vec<char>], quantity: usize, src: usize, dst: usize) {
+        let batch = self.max_batch().map_or(quantity, NonZeroUsize::get);
+
+        let mut remaining = quantity;
+        while remaining > 0 {
+            let lifted = remaining.min(batch);
+            let split = stacks[src].len() - lifted;
+            let crates = stacks[src].split_off(split);
+            stacks[dst].extend(crates);
+            remaining -= lifted;
+        }
+    }
+}
+
+/// Moves crates one at a time
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn max_batch(&self) -> Option<NonZeroUsize> {
+        Some(NonZeroUsize::MIN)
+    }
+}
+
+/// Moves every crate of a command at once
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn max_batch(&self) -> Option<NonZeroUsize> {
+        None
+    }
+}
+
+/// Moves crates by batches of at most `max_batch` crates
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct BatchCrane {
+    pub max_batch: NonZeroUsize,
+}
+
+impl BatchCrane {
+    /// A crane lifting at most `max_batch` crates, `None` if it cannot lift any crate
+    pub fn new(max_batch: usize) -> Option<Self> {
+        NonZeroUsize::new(max_batch).map(|max_batch| Self { max_batch })
+    }
+}
+
+impl Crane for BatchCrane {
+    fn max_batch(&self) -> Option<NonZeroUsize> {
+        Some(self.max_batch)
+    }
+}
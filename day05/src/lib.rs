@@ -0,0 +1,5 @@
+pub mod crane;
+pub mod model;
+pub mod parser;
+pub mod replay;
+pub mod synthesizer;
@@ -1,128 +1,134 @@
-use std::{env, fs};
-
-#[derive(Debug, Clone)]
-struct Command {
-    quantity: usize,
-    src: usize,
-    dst: usize,
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+
+use day05::crane::{BatchCrane, Crane, CrateMover9000, CrateMover9001};
+use day05::model::Game;
+use day05::replay::Replay;
+use day05::synthesizer::{synthesize, DEFAULT_SEARCH_LIMIT};
+
+#[derive(ValueEnum, Debug, Eq, PartialEq, Clone, Copy)]
+enum CraneModel {
+    #[value(name = "9000")]
+    CrateMover9000,
+    #[value(name = "9001")]
+    CrateMover9001,
 }
 
-impl From<&str> for Command {
-    fn from(value: &str) -> Self {
-        let (_, rem) = value.split_once("move ").unwrap();
-        let (quantity, rem) = rem.split_once(" from ").unwrap();
-        let (src, dst) = rem.split_once(" to ").unwrap();
-        Self {
-            quantity: quantity.parse().unwrap(),
-            src: src.parse().unwrap(),
-            dst: dst.parse().unwrap(),
-        }
-    }
-}
+#[derive(Parser, Debug, Eq, PartialEq, Clone)]
+struct Cli {
+    /// Enable verbose display
+    #[arg(short, long, default_value_t = false)]
+    verbose: bool,
 
-#[derive(Debug, Clone)]
-struct Game {
-    stacks: Vec<Vec<char>>,
-    commands: Vec<Command>,
-}
+    /// Also simulate a crane lifting at most this number of crates at once
+    #[arg(short, long)]
+    max_batch: Option<NonZeroUsize>,
 
-impl Game {
-    pub fn new(data: &str) -> Self {
-        // let data: Vec<Vec<char>> = data.lines().map(|line| line.chars().collect()).collect();
-
-        // determinate the line containing the base of the stack
-        // We suppose that the base has less than 9 stacks to simplify the parsing
-        let mut base: usize = 0;
-        let mut stack_count: usize = 0;
-        for (i, line) in data.lines().enumerate() {
-            if line.starts_with(" 1 ") {
-                base = i;
-                stack_count = (line.len() + 2) / 4;
-                break;
-            }
-        }
+    /// Drawing of the arrangement to reach, commands moving the input stacks to it are generated
+    #[arg(short, long)]
+    goal: Option<PathBuf>,
 
-        // Get an array of char for stack reading
-        let stack_input: Vec<Vec<char>> = data
-            .lines()
-            .map(|line| line.chars().collect())
-            .take(base)
-            .collect();
-
-        // Initialize the stack, we create on more vector than required in order to
-        // be able to use command indices in order to access them.
-        let mut stacks: Vec<Vec<char>> = vec![Vec::new(); stack_count + 1];
-
-        // Now parse the input from the base to the top in order to fill the stacks starting pos
-        for i in (0..base).rev() {
-            for stack in 1..=stack_count {
-                let stack_value = stack_input[i][(stack * 4) - 3];
-                if stack_value != ' ' {
-                    stacks[stack].push(stack_value)
-                }
-            }
-        }
+    /// Crane used to generate the commands, unless a maximum batch size is given
+    #[arg(short, long, value_enum, default_value_t = CraneModel::CrateMover9001)]
+    crane: CraneModel,
 
-        // Parse command by skipping the base definition
-        let commands: Vec<Command> = data.lines().skip(base + 2).map(From::from).collect();
+    /// Maximum number of generated commands
+    #[arg(long)]
+    max_length: Option<usize>,
 
-        Self { stacks, commands }
-    }
+    /// Maximum number of stack configurations explored while generating the commands
+    #[arg(long, default_value_t = DEFAULT_SEARCH_LIMIT)]
+    search_limit: usize,
 
-    fn simulate_day_01(&mut self) {
-        for command in &self.commands {
-            for _ in 0..command.quantity {
-                let crate_to_move = self.stacks[command.src].pop().unwrap();
-                self.stacks[command.dst].push(crate_to_move);
-            }
-        }
-    }
+    /// File to parse
+    #[arg(default_value = "input")]
+    path: PathBuf,
+}
 
-    fn simulate_day_02(&mut self) {
-        for command in &self.commands {
-            let mut crates_to_move = Vec::new();
-            for _ in 0..command.quantity {
-                crates_to_move.push(self.stacks[command.src].pop().unwrap());
-            }
-            self.stacks[command.dst].extend(crates_to_move.iter().rev());
+fn simulate<C: Crane>(verbose: bool, game: Game, crane: C) -> Result<String, String> {
+    let mut replay = Replay::new(game, crane);
+
+    while replay.step().map_err(|error| error.to_string())? {
+        if verbose {
+            let index = replay.position() - 1;
+            println!("{}", replay.game().commands[index]);
+            println!("{}", replay.drawing_after(index).unwrap());
         }
     }
 
-    fn get_result(&self) -> String {
-        self.stacks
-            .iter()
-            .skip(1)
-            .map(|stack| stack[stack.len() - 1])
-            .collect()
+    Ok(replay.game().get_result())
+}
+
+fn solve_part_one(verbose: bool, game: Game) {
+    match simulate(verbose, game, CrateMover9000) {
+        Ok(res) => println!("Part one solution: {:#?}", res),
+        Err(error) => println!("Part one failed: {}", error),
     }
 }
 
-fn solve_part_one(mut game: Game) {
-    game.simulate_day_01();
-    let res = game.get_result();
+fn solve_part_two(verbose: bool, game: Game) {
+    match simulate(verbose, game, CrateMover9001) {
+        Ok(res) => println!("Part two solution: {:#?}", res),
+        Err(error) => println!("Part two failed: {}", error),
+    }
+}
 
-    println!("Part one solution: {:#?}", res);
+fn solve_batch(verbose: bool, game: Game, max_batch: NonZeroUsize) {
+    match simulate(verbose, game, BatchCrane { max_batch }) {
+        Ok(res) => println!("Batch of {} solution: {:#?}", max_batch, res),
+        Err(error) => println!("Batch of {} failed: {}", max_batch, error),
+    }
 }
 
-fn solve_part_two(mut game: Game) {
-    game.simulate_day_02();
-    let res = game.get_result();
+fn synthesize_commands(game: Game, goal: &Game, crane: &dyn Crane, args: &Cli) {
+    match synthesize(&game, goal, crane, args.max_length, args.search_limit) {
+        Ok(commands) => print!("{}", Game { commands, ..game }),
+        Err(error) => println!("Unable to generate commands: {}", error),
+    }
+}
 
-    println!("Part one solution: {:#?}", res);
+fn parse_game(path: &PathBuf) -> Game {
+    let data: String = fs::read_to_string(path).unwrap();
+    match Game::new(&data) {
+        Ok(game) => game,
+        Err(error) => {
+            eprintln!("Unable to parse {}: {}", path.display(), error);
+            std::process::exit(1);
+        }
+    }
 }
 
 fn main() {
-    let args: Vec<_> = env::args().collect();
+    let args = Cli::parse();
 
-    let file_path = if args.len() > 1 {
-        args[1].clone()
-    } else {
-        "input".to_string()
-    };
+    let game = parse_game(&args.path);
 
-    let data: String = fs::read_to_string(&file_path).unwrap();
-    let game = Game::new(&data);
+    if let Some(goal) = &args.goal {
+        let goal = parse_game(goal);
+        match (args.max_batch, args.crane) {
+            (Some(max_batch), _) => {
+                synthesize_commands(game, &goal, &BatchCrane { max_batch }, &args)
+            }
+            (None, CraneModel::CrateMover9000) => {
+                synthesize_commands(game, &goal, &CrateMover9000, &args)
+            }
+            (None, CraneModel::CrateMover9001) => {
+                synthesize_commands(game, &goal, &CrateMover9001, &args)
+            }
+        }
+        return;
+    }
+
+    if args.verbose {
+        println!("{}", game.drawing());
+    }
 
-    solve_part_one(game.clone());
-    solve_part_two(game);
+    solve_part_one(args.verbose, game.clone());
+    solve_part_two(args.verbose, game.clone());
+    if let Some(max_batch) = args.max_batch {
+        solve_batch(args.verbose, game, max_batch);
+    }
 }
@@ -0,0 +1,200 @@
+use std::fmt::{Display, Formatter};
+
+use crate::crane::Crane;
+use crate::parser::parse_game;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum GameError {
+    /// The drawing does not end with a line of stack labels
+    MissingBase,
+    /// A line of the drawing contains something else than crates and spaces
+    InvalidDrawing { line: usize, content: String },
+    /// Two stacks share the same label
+    DuplicateStack { label: usize },
+    /// A command line could not be parsed
+    InvalidCommand { line: usize, content: String },
+    /// A command references a stack label that is not part of the drawing
+    UnknownStack { command: usize, label: usize },
+    /// A command tries to take more crates than the stack holds
+    EmptyStack { command: usize, label: usize },
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameError::MissingBase => write!(f, "the drawing has no line of stack labels"),
+            GameError::InvalidDrawing { line, content } => {
+                write!(f, "line {}: invalid drawing line '{}'", line, content)
+            }
+            GameError::DuplicateStack { label } => write!(f, "stack {} is defined twice", label),
+            GameError::InvalidCommand { line, content } => {
+                write!(f, "line {}: invalid command '{}'", line, content)
+            }
+            GameError::UnknownStack { command, label } => {
+                write!(f, "command {}: stack {} does not exist", command, label)
+            }
+            GameError::EmptyStack { command, label } => {
+                write!(
+                    f,
+                    "command {}: stack {} does not hold enough crates",
+                    command, label
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Command {
+    pub quantity: usize,
+    pub src: usize,
+    pub dst: usize,
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.quantity, self.src, self.dst
+        )
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Game {
+    /// Label of every stack, in drawing order
+    pub labels: Vec<usize>,
+    /// Crates of every stack, from the bottom to the top
+    pub stacks: Vec<Vec<char>>,
+    pub commands: Vec<Command>,
+}
+
+impl Game {
+    pub fn new(data: &str) -> Result<Self, GameError> {
+        parse_game(data)
+    }
+
+    /// Get the index in `stacks` of the stack with the given label
+    pub fn stack_index(&self, label: usize) -> Option<usize> {
+        self.labels.iter().position(|&l| l == label)
+    }
+
+    /// Get the source and destination indices of a command and make sure the source stack holds
+    /// enough crates.
+    pub fn resolve(&self, index: usize, command: &Command) -> Result<(usize, usize), GameError> {
+        let src = self
+            .stack_index(command.src)
+            .ok_or(GameError::UnknownStack {
+                command: index,
+                label: command.src,
+            })?;
+        let dst = self
+            .stack_index(command.dst)
+            .ok_or(GameError::UnknownStack {
+                command: index,
+                label: command.dst,
+            })?;
+
+        if self.stacks[src].len() < command.quantity {
+            return Err(GameError::EmptyStack {
+                command: index,
+                label: command.src,
+            });
+        }
+
+        Ok((src, dst))
+    }
+
+    /// Execute the command at the given index with the given crane
+    pub fn apply(&mut self, index: usize, crane: &dyn Crane) -> Result<(), GameError> {
+        let command = &self.commands[index];
+        let (src, dst) = self.resolve(index, command)?;
+        crane.move_crates(&mut self.stacks, command.quantity, src, dst);
+
+        Ok(())
+    }
+
+    /// Execute every command with the given crane
+    pub fn simulate(&mut self, crane: &dyn Crane) -> Result<(), GameError> {
+        for index in 0..self.commands.len() {
+            self.apply(index, crane)?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the crate on top of every non-empty stack
+    pub fn get_result(&self) -> String {
+        self.stacks
+            .iter()
+            .filter_map(|stack| stack.last())
+            .collect()
+    }
+
+    pub fn drawing(&self) -> Drawing<'_> {
+        Drawing {
+            labels: &self.labels,
+            stacks: &self.stacks,
+        }
+    }
+}
+
+/// Stacks displayed using the puzzle drawing format: every stack uses three columns, or more when
+/// a label is wider than a crate, and is separated from the next one by a space.
+pub struct Drawing<'a> {
+    pub labels: &'a [usize],
+    pub stacks: &'a [Vec<char>],
+}
+
+impl Display for Drawing<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let height = self
+            .stacks
+            .iter()
+            .map(|stack| stack.len())
+            .max()
+            .unwrap_or(0);
+        let width = self
+            .labels
+            .iter()
+            .map(|label| label.to_string().len())
+            .max()
+            .unwrap_or(0)
+            .max(3);
+
+        for level in (0..height).rev() {
+            let line: Vec<String> = self
+                .stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(value) => format!("{:^width$}", format!("[{}]", value)),
+                    None => " ".repeat(width),
+                })
+                .collect();
+            writeln!(f, "{}", line.join(" "))?;
+        }
+
+        let base: Vec<String> = self
+            .labels
+            .iter()
+            .map(|label| format!("{:^width$}", label))
+            .collect();
+        writeln!(f, "{}", base.join(" "))
+    }
+}
+
+impl Display for Game {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.drawing())?;
+
+        if !self.commands.is_empty() {
+            writeln!(f)?;
+            for command in &self.commands {
+                writeln!(f, "{}", command)?;
+            }
+        }
+
+        Ok(())
+    }
+}
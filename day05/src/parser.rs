@@ -0,0 +1,180 @@
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use nom::bytes::complete::tag;
+use nom::character::complete::digit1;
+use nom::combinator::{all_consuming, map_res};
+use nom::sequence::tuple;
+use nom::{IResult, Parser};
+
+use crate::model::{Command, Game, GameError};
+
+pub fn parse_command(input: &str) -> IResult<&str, Command> {
+    map_res(
+        tuple((
+            tag("move "),
+            digit1,
+            tag(" from "),
+            digit1,
+            tag(" to "),
+            digit1,
+        )),
+        |(_, quantity, _, src, _, dst)| {
+            Ok::<_, ParseIntError>(Command {
+                quantity: usize::from_str(quantity)?,
+                src: usize::from_str(src)?,
+                dst: usize::from_str(dst)?,
+            })
+        },
+    )
+    .parse(input)
+}
+
+/// Parse the base of the drawing, returning every label with the range of columns it spans.
+pub fn parse_base(line: &str) -> Result<Vec<(usize, usize, usize)>, GameError> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut labels = vec![];
+
+    let mut column = 0;
+    while column < chars.len() {
+        if chars[column] == ' ' {
+            column += 1;
+        } else if chars[column].is_ascii_digit() {
+            let start = column;
+            while column < chars.len() && chars[column].is_ascii_digit() {
+                column += 1;
+            }
+            let label: String = chars[start..column].iter().collect();
+            labels.push((label.parse().unwrap(), start, column - 1));
+        } else {
+            return Err(GameError::MissingBase);
+        }
+    }
+
+    if labels.is_empty() {
+        return Err(GameError::MissingBase);
+    }
+
+    Ok(labels)
+}
+
+/// Get the stack whose label is the closest to the given column.
+fn closest_stack(labels: &[(usize, usize, usize)], column: usize) -> usize {
+    labels
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, (_, start, end))| {
+            if column < *start {
+                start - column
+            } else {
+                column.saturating_sub(*end)
+            }
+        })
+        .map(|(index, _)| index)
+        .unwrap()
+}
+
+/// Parse a stack drawing. Lines may be ragged, and crates are assigned to the stack whose label
+/// is the closest to them, which allows any number of stacks and multi-digit labels.
+pub fn parse_drawing(lines: &[&str]) -> Result<(Vec<usize>, Vec<Vec<char>>), GameError> {
+    let (base, crate_lines) = lines.split_last().ok_or(GameError::MissingBase)?;
+    let labels_with_span = parse_base(base)?;
+
+    let labels: Vec<usize> = labels_with_span
+        .iter()
+        .map(|(label, _, _)| *label)
+        .collect();
+    for (i, label) in labels.iter().enumerate() {
+        if labels[..i].contains(label) {
+            return Err(GameError::DuplicateStack { label: *label });
+        }
+    }
+
+    let mut stacks: Vec<Vec<char>> = vec![Vec::new(); labels.len()];
+    // A stack is closed once an empty slot is found, no crate can be above it
+    let mut closed = vec![false; labels.len()];
+
+    // Parse the drawing from the base to the top in order to fill the stacks
+    for (line_index, line) in crate_lines.iter().enumerate().rev() {
+        let invalid = || GameError::InvalidDrawing {
+            line: line_index + 1,
+            content: line.to_string(),
+        };
+
+        let chars: Vec<char> = line.chars().collect();
+        let mut filled = vec![false; labels.len()];
+
+        let mut column = 0;
+        while column < chars.len() {
+            match chars[column] {
+                ' ' => column += 1,
+                '[' => {
+                    if chars.get(column + 2) != Some(&']') || chars[column + 1] == ' ' {
+                        return Err(invalid());
+                    }
+
+                    let stack = closest_stack(&labels_with_span, column + 1);
+                    if filled[stack] || closed[stack] {
+                        return Err(invalid());
+                    }
+                    filled[stack] = true;
+                    stacks[stack].push(chars[column + 1]);
+
+                    column += 3;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        for (closed, filled) in closed.iter_mut().zip(filled) {
+            *closed |= !filled;
+        }
+    }
+
+    Ok((labels, stacks))
+}
+
+pub fn parse_game(data: &str) -> Result<Game, GameError> {
+    let lines: Vec<&str> = data.lines().collect();
+
+    // The drawing is separated from the commands by an empty line
+    let separator = lines
+        .iter()
+        .position(|line| line.trim().is_empty())
+        .unwrap_or(lines.len());
+
+    let (labels, stacks) = parse_drawing(&lines[..separator])?;
+
+    let commands = lines
+        .iter()
+        .enumerate()
+        .skip(separator + 1)
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(line_index, line)| {
+            all_consuming(parse_command)
+                .parse(line.trim_end())
+                .map(|(_, command)| command)
+                .map_err(|_| GameError::InvalidCommand {
+                    line: line_index + 1,
+                    content: line.to_string(),
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (index, command) in commands.iter().enumerate() {
+        for label in [command.src, command.dst] {
+            if !labels.contains(&label) {
+                return Err(GameError::UnknownStack {
+                    command: index,
+                    label,
+                });
+            }
+        }
+    }
+
+    Ok(Game {
+        labels,
+        stacks,
+        commands,
+    })
+}
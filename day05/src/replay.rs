@@ -0,0 +1,92 @@
+use crate::crane::Crane;
+use crate::model::{Drawing, Game, GameError};
+
+/// Step-by-step simulation of a game that keeps the stacks before every executed command, so
+/// that commands can be undone and any intermediate configuration can be queried.
+pub struct Replay<C: Crane> {
+    crane: C,
+    game: Game,
+    /// Stacks before each executed command
+    history: Vec<Vec<Vec<char>>>,
+}
+
+impl<C: Crane> Replay<C> {
+    pub fn new(game: Game, crane: C) -> Self {
+        Self {
+            crane,
+            game,
+            history: vec![],
+        }
+    }
+
+    /// Number of commands executed so far
+    pub fn position(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position() == self.game.commands.len()
+    }
+
+    /// Current state of the game
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Execute the next command. Return `false` if every command was already executed.
+    pub fn step(&mut self) -> Result<bool, GameError> {
+        if self.is_finished() {
+            return Ok(false);
+        }
+
+        let stacks = self.game.stacks.clone();
+        self.game.apply(self.position(), &self.crane)?;
+        self.history.push(stacks);
+
+        Ok(true)
+    }
+
+    /// Execute every remaining command
+    pub fn run(&mut self) -> Result<(), GameError> {
+        while self.step()? {}
+
+        Ok(())
+    }
+
+    /// Revert the last executed command. Return `false` if no command was executed.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            None => false,
+            Some(stacks) => {
+                self.game.stacks = stacks;
+                true
+            }
+        }
+    }
+
+    /// Step forward or undo commands until exactly `position` commands are executed
+    pub fn seek(&mut self, position: usize) -> Result<(), GameError> {
+        while self.position() > position {
+            self.undo();
+        }
+        while self.position() < position && self.step()? {}
+
+        Ok(())
+    }
+
+    /// Get the stacks right after the command at the given index was executed, if it was
+    pub fn stacks_after(&self, index: usize) -> Option<&[Vec<char>]> {
+        match (index + 1).cmp(&self.position()) {
+            std::cmp::Ordering::Less => Some(&self.history[index + 1]),
+            std::cmp::Ordering::Equal => Some(&self.game.stacks),
+            std::cmp::Ordering::Greater => None,
+        }
+    }
+
+    pub fn drawing_after(&self, index: usize) -> Option<Drawing<'_>> {
+        self.stacks_after(index).map(|stacks| Drawing {
+            labels: &self.game.labels,
+            stacks,
+        })
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+
+use crate::crane::Crane;
+use crate::model::{Command, Game};
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SynthesisError {
+    /// The start and goal drawings do not have the same stacks
+    LabelsMismatch,
+    /// The start and goal drawings do not hold the same crates
+    CratesMismatch,
+    /// No command sequence reaches the goal within the length bound
+    NotFound,
+    /// The search reached too many configurations before finding the goal
+    SearchLimit { limit: usize },
+}
+
+impl Display for SynthesisError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SynthesisError::LabelsMismatch => {
+                write!(f, "start and goal drawings have different stacks")
+            }
+            SynthesisError::CratesMismatch => {
+                write!(f, "start and goal drawings hold different crates")
+            }
+            SynthesisError::NotFound => write!(f, "no command sequence reaches the goal"),
+            SynthesisError::SearchLimit { limit } => write!(
+                f,
+                "no command sequence found after reaching {} configurations",
+                limit
+            ),
+        }
+    }
+}
+
+type Stacks = Vec<Vec<char>>;
+
+/// Default number of configurations reached by `synthesize` before giving up
+pub const DEFAULT_SEARCH_LIMIT: usize = 1_000_000;
+
+fn sorted_crates(stacks: &[Vec<char>]) -> Vec<char> {
+    let mut crates: Vec<char> = stacks.iter().flatten().copied().collect();
+    crates.sort_unstable();
+    crates
+}
+
+/// Find a shortest list of commands moving the crates of `start` into the arrangement of `goal`
+/// with the given crane. The search is a breadth-first search over stack configurations, so it
+/// is only practical for small drawings: it gives up once `search_limit` configurations are
+/// reached. Commands of `start` and `goal` are ignored.
+pub fn synthesize(
+    start: &Game,
+    goal: &Game,
+    crane: &dyn Crane,
+    max_length: Option<usize>,
+    search_limit: usize,
+) -> Result<Vec<Command>, SynthesisError> {
+    if start.labels != goal.labels {
+        return Err(SynthesisError::LabelsMismatch);
+    }
+    if sorted_crates(&start.stacks) != sorted_crates(&goal.stacks) {
+        return Err(SynthesisError::CratesMismatch);
+    }
+
+    // For every reached configuration, by order of discovery, the index of the configuration it
+    // comes from and the command used
+    let mut parents: Vec<Option<(usize, Command)>> = vec![None];
+    let mut reached: HashSet<Stacks> = HashSet::from([start.stacks.clone()]);
+    let mut queue = VecDeque::from([(start.stacks.clone(), 0, 0)]);
+
+    while let Some((stacks, index, length)) = queue.pop_front() {
+        if stacks == goal.stacks {
+            let mut commands = vec![];
+            let mut current = index;
+            while let Some((parent, command)) = &parents[current] {
+                commands.push(command.clone());
+                current = *parent;
+            }
+            commands.reverse();
+
+            return Ok(commands);
+        }
+
+        if max_length.is_some_and(|max_length| length >= max_length) {
+            continue;
+        }
+
+        for src in 0..stacks.len() {
+            for dst in 0..stacks.len() {
+                if src == dst {
+                    continue;
+                }
+
+                for quantity in 1..=stacks[src].len() {
+                    let mut next = stacks.clone();
+                    crane.move_crates(&mut next, quantity, src, dst);
+
+                    if reached.contains(&next) {
+                        continue;
+                    }
+                    if reached.len() >= search_limit {
+                        return Err(SynthesisError::SearchLimit {
+                            limit: search_limit,
+                        });
+                    }
+
+                    let command = Command {
+                        quantity,
+                        src: start.labels[src],
+                        dst: start.labels[dst],
+                    };
+                    reached.insert(next.clone());
+                    parents.push(Some((index, command)));
+                    queue.push_back((next, parents.len() - 1, length + 1));
+                }
+            }
+        }
+    }
+
+    Err(SynthesisError::NotFound)
+}
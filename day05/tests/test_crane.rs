@@ -0,0 +1,95 @@
+use day05::crane::{BatchCrane, Crane, CrateMover9000, CrateMover9001};
+use day05::model::Game;
+use day05::replay::Replay;
+
+static EXAMPLE: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+fn simulate_with(crane: &dyn Crane) -> String {
+    let mut game = Game::new(EXAMPLE).unwrap();
+    game.simulate(crane).unwrap();
+    game.get_result()
+}
+
+#[test]
+fn test_cranes() {
+    assert_eq!(simulate_with(&CrateMover9000), "CMZ");
+    assert_eq!(simulate_with(&CrateMover9001), "MCD");
+    assert_eq!(simulate_with(&BatchCrane::new(1).unwrap()), "CMZ");
+    assert_eq!(simulate_with(&BatchCrane::new(3).unwrap()), "MCD");
+}
+
+#[test]
+fn test_batch_crane_zero() {
+    assert_eq!(BatchCrane::new(0), None);
+}
+
+#[test]
+fn test_batch_crane() {
+    let mut game = Game::new(
+        "[A]
+[B]
+[C]
+[D]
+[E]
+ 1   2
+
+move 5 from 1 to 2
+",
+    )
+    .unwrap();
+    game.simulate(&BatchCrane::new(2).unwrap()).unwrap();
+
+    assert_eq!(game.stacks, vec![vec![], vec!['B', 'A', 'D', 'C', 'E']]);
+}
+
+#[test]
+fn test_replay() {
+    let game = Game::new(EXAMPLE).unwrap();
+    let initial = game.stacks.clone();
+    let mut replay = Replay::new(game, CrateMover9000);
+
+    assert_eq!(replay.stacks_after(0), None);
+    assert!(replay.step().unwrap());
+    assert_eq!(
+        replay.stacks_after(0).unwrap(),
+        &[vec!['Z', 'N', 'D'], vec!['M', 'C'], vec!['P']]
+    );
+
+    replay.run().unwrap();
+    assert!(replay.is_finished());
+    assert!(!replay.step().unwrap());
+    assert_eq!(replay.game().get_result(), "CMZ");
+    assert_eq!(
+        replay.stacks_after(1).unwrap(),
+        &[vec![], vec!['M', 'C'], vec!['P', 'D', 'N', 'Z']]
+    );
+
+    assert!(replay.undo());
+    assert_eq!(replay.position(), 3);
+    assert_eq!(replay.stacks_after(3), None);
+
+    replay.seek(0).unwrap();
+    assert_eq!(replay.game().stacks, initial);
+    assert!(!replay.undo());
+
+    replay.seek(2).unwrap();
+    assert_eq!(replay.position(), 2);
+    assert_eq!(
+        replay.drawing_after(1).unwrap().to_string(),
+        "        [Z]
+        [N]
+    [C] [D]
+    [M] [P]
+ 1   2   3 
+"
+    );
+}
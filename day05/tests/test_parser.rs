@@ -0,0 +1,133 @@
+use day05::crane::{CrateMover9000, CrateMover9001};
+use day05::model::{Command, Game, GameError};
+use day05::parser::{parse_base, parse_command};
+
+static EXAMPLE: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+#[test]
+fn test_parse_command() {
+    assert_eq!(
+        parse_command("move 12 from 10 to 2"),
+        Ok((
+            "",
+            Command {
+                quantity: 12,
+                src: 10,
+                dst: 2
+            }
+        ))
+    );
+}
+
+#[test]
+fn test_parse_base() {
+    assert_eq!(
+        parse_base(" 1   2   9  10  11 "),
+        Ok(vec![
+            (1, 1, 1),
+            (2, 5, 5),
+            (9, 9, 9),
+            (10, 12, 13),
+            (11, 16, 17)
+        ])
+    );
+    assert_eq!(parse_base("[A] [B]"), Err(GameError::MissingBase));
+}
+
+#[test]
+fn test_parse_game() {
+    let game = Game::new(EXAMPLE).unwrap();
+
+    assert_eq!(game.labels, vec![1, 2, 3]);
+    assert_eq!(
+        game.stacks,
+        vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]
+    );
+    assert_eq!(game.commands.len(), 4);
+}
+
+#[test]
+fn test_parse_ragged_many_stacks() {
+    let data = "[A]                                     [K]
+[B] [C] [D] [E] [F] [G] [H] [I] [J] [L] [M]
+ 1   2   3   4   5   6   7   8   9  10  11
+
+move 2 from 11 to 10
+";
+    let mut game = Game::new(data).unwrap();
+
+    assert_eq!(game.stacks.len(), 11);
+    assert_eq!(game.stacks[9], vec!['L']);
+    assert_eq!(game.stacks[10], vec!['M', 'K']);
+
+    game.simulate(&CrateMover9001).unwrap();
+    assert_eq!(game.get_result(), "ACDEFGHIJK");
+}
+
+#[test]
+fn test_round_trip() {
+    let game = Game::new(EXAMPLE).unwrap();
+
+    assert_eq!(game.to_string(), EXAMPLE);
+    assert_eq!(Game::new(&game.to_string()), Ok(game));
+}
+
+#[test]
+fn test_round_trip_wide_labels() {
+    let data = "[A]        [D]
+[B] [C]    [E]
+ 1  1000  12345
+
+move 2 from 12345 to 1000
+";
+    let game = Game::new(data).unwrap();
+    assert_eq!(game.stacks, vec![vec!['B', 'A'], vec!['C'], vec!['E', 'D']]);
+
+    let drawn = game.to_string();
+    assert_eq!(
+        drawn,
+        " [A]         [D] 
+ [B]   [C]   [E] 
+  1   1000  12345
+
+move 2 from 12345 to 1000
+"
+    );
+    assert_eq!(Game::new(&drawn), Ok(game));
+}
+
+#[test]
+fn test_invalid_commands() {
+    assert_eq!(
+        Game::new(&EXAMPLE.replace("move 3 from 1 to 3", "move 3 from 1 to 4")),
+        Err(GameError::UnknownStack {
+            command: 1,
+            label: 4
+        })
+    );
+    assert_eq!(
+        Game::new(&EXAMPLE.replace("move 3 from 1 to 3", "move three from 1 to 3")),
+        Err(GameError::InvalidCommand {
+            line: 7,
+            content: "move three from 1 to 3".to_string()
+        })
+    );
+
+    let mut game = Game::new(&EXAMPLE.replace("move 1 from 2 to 1", "move 4 from 2 to 1")).unwrap();
+    assert_eq!(
+        game.simulate(&CrateMover9000),
+        Err(GameError::EmptyStack {
+            command: 0,
+            label: 2
+        })
+    );
+}
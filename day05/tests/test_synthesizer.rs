@@ -0,0 +1,101 @@
+use day05::crane::{Crane, CrateMover9000, CrateMover9001};
+use day05::model::Game;
+use day05::synthesizer::{synthesize, SynthesisError, DEFAULT_SEARCH_LIMIT};
+
+static START: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+";
+
+static GOAL: &str = "        [Z]
+        [N]
+        [D]
+[C] [M] [P]
+ 1   2   3 
+";
+
+#[test]
+fn test_synthesize() {
+    let start = Game::new(START).unwrap();
+    let goal = Game::new(GOAL).unwrap();
+
+    for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001] {
+        let commands = synthesize(&start, &goal, crane, None, DEFAULT_SEARCH_LIMIT).unwrap();
+
+        // Feed the generated commands back to the parser and check the result
+        let program = Game {
+            commands: commands.clone(),
+            ..start.clone()
+        };
+        let mut game = Game::new(&program.to_string()).unwrap();
+        game.simulate(crane).unwrap();
+        assert_eq!(game.stacks, goal.stacks);
+
+        // The example moves of the puzzle reach the same arrangement in 4 commands
+        assert!(commands.len() <= 4);
+    }
+}
+
+#[test]
+fn test_synthesize_shortest() {
+    let start = Game::new(START).unwrap();
+    let goal = Game::new(
+        "[D]        
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        synthesize(&start, &goal, &CrateMover9000, None, DEFAULT_SEARCH_LIMIT)
+            .unwrap()
+            .iter()
+            .map(|command| command.to_string())
+            .collect::<Vec<_>>(),
+        vec!["move 1 from 2 to 1"]
+    );
+}
+
+#[test]
+fn test_synthesize_errors() {
+    let start = Game::new(START).unwrap();
+    let goal = Game::new(GOAL).unwrap();
+
+    assert_eq!(
+        synthesize(
+            &start,
+            &goal,
+            &CrateMover9001,
+            Some(1),
+            DEFAULT_SEARCH_LIMIT
+        ),
+        Err(SynthesisError::NotFound)
+    );
+    assert_eq!(
+        synthesize(&start, &goal, &CrateMover9000, None, 10),
+        Err(SynthesisError::SearchLimit { limit: 10 })
+    );
+    assert_eq!(
+        synthesize(
+            &start,
+            &Game::new(&GOAL.replace('Z', "Y")).unwrap(),
+            &CrateMover9001,
+            None,
+            DEFAULT_SEARCH_LIMIT
+        ),
+        Err(SynthesisError::CratesMismatch)
+    );
+    assert_eq!(
+        synthesize(
+            &start,
+            &Game::new("[Z] [N] [D] [C] [M] [P]\n 1   2   3   4   5   6\n").unwrap(),
+            &CrateMover9001,
+            None,
+            DEFAULT_SEARCH_LIMIT
+        ),
+        Err(SynthesisError::LabelsMismatch)
+    );
+}
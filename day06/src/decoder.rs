@@ -0,0 +1,132 @@
+use std::io::Read;
+use std::ops::Range;
+
+use crate::detector::{stream_bytes, MarkerDetector};
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum FrameKind {
+    /// Data following a start-of-packet marker, up to the first start-of-message marker
+    Packet,
+    /// Data following a start-of-message marker
+    Message,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Frame {
+    pub kind: FrameKind,
+    /// Offsets of the marker starting the frame
+    pub marker: Range<usize>,
+    /// Offsets of the frame content
+    pub payload: Range<usize>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct Decoded {
+    pub frames: Vec<Frame>,
+    /// Regions longer than the maximum distance that do not contain any marker
+    pub corruptions: Vec<Range<usize>>,
+}
+
+/// Split a datastream into frames.
+///
+/// The decoder first looks for a start-of-packet marker, which opens a packet frame. Inside a
+/// packet, every start-of-message marker closes the current frame and opens a message frame.
+/// Markers never overlap: detection starts over after each one of them.
+///
+/// When a maximum distance is set and no marker is found within that distance of the previous
+/// one, the region is reported as corrupted, the current frame is closed and the decoder looks
+/// for a start-of-packet marker again.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Decoder {
+    pub packet_window: usize,
+    pub message_window: usize,
+    pub max_distance: Option<usize>,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self {
+            packet_window: 4,
+            message_window: 14,
+            max_distance: None,
+        }
+    }
+}
+
+impl Decoder {
+    pub fn decode<R: Read>(&self, reader: R) -> std::io::Result<Decoded> {
+        let mut packet_detector = MarkerDetector::new(self.packet_window);
+        let mut message_detector = MarkerDetector::new(self.message_window);
+
+        let mut decoded = Decoded::default();
+        let mut current: Option<Frame> = None;
+        // End of the last marker, start of the region without marker
+        let mut gap_start = 0;
+        let mut position = 0;
+
+        for value in stream_bytes(reader) {
+            let value = value?;
+            position += 1;
+
+            match current.take() {
+                None => {
+                    if packet_detector.push(value) {
+                        let marker = position - self.packet_window..position;
+                        self.check_gap(&mut decoded, gap_start..marker.start);
+
+                        current = Some(Frame {
+                            kind: FrameKind::Packet,
+                            marker,
+                            payload: position..position,
+                        });
+                        message_detector.reset();
+                        gap_start = position;
+                    }
+                }
+                Some(mut frame) => {
+                    if message_detector.push(value) {
+                        let marker = position - self.message_window..position;
+
+                        frame.payload.end = marker.start;
+                        decoded.frames.push(frame);
+
+                        current = Some(Frame {
+                            kind: FrameKind::Message,
+                            marker,
+                            payload: position..position,
+                        });
+                        message_detector.reset();
+                        gap_start = position;
+                    } else if self
+                        .max_distance
+                        .is_some_and(|max_distance| position - gap_start > max_distance)
+                    {
+                        // Resynchronize on the next packet
+                        frame.payload.end = position;
+                        decoded.frames.push(frame);
+                        packet_detector.reset();
+                    } else {
+                        current = Some(frame);
+                    }
+                }
+            }
+        }
+
+        if let Some(mut frame) = current {
+            frame.payload.end = position;
+            decoded.frames.push(frame);
+        }
+        self.check_gap(&mut decoded, gap_start..position);
+
+        Ok(decoded)
+    }
+
+    fn check_gap(&self, decoded: &mut Decoded, gap: Range<usize>) {
+        if self
+            .max_distance
+            .is_some_and(|max_distance| gap.len() > max_distance)
+        {
+            decoded.corruptions.push(gap);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::VecDeque;
+use std::io::{BufReader, Read};
+
+/// Detect markers, i.e. sequences of `window` different characters, in a datastream.
+/// Characters are fed one by one and a count of every character of the current window is kept up
+/// to date, so each character is processed in constant time whatever the window size.
+#[derive(Debug, Clone)]
+pub struct MarkerDetector {
+    window: usize,
+    buffer: VecDeque<u8>,
+    counts: [usize; 256],
+    /// Number of characters appearing exactly once in the window
+    unique: usize,
+    /// Number of characters processed so far
+    position: usize,
+}
+
+impl MarkerDetector {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            buffer: VecDeque::with_capacity(window + 1),
+            counts: [0; 256],
+            unique: 0,
+            position: 0,
+        }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Number of characters processed so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Forget the characters seen so far, the position is kept
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.counts = [0; 256];
+        self.unique = 0;
+    }
+
+    /// Feed the next character of the stream. Return `true` if it ends a marker.
+    pub fn push(&mut self, value: u8) -> bool {
+        self.position += 1;
+
+        self.buffer.push_back(value);
+        self.counts[value as usize] += 1;
+        match self.counts[value as usize] {
+            1 => self.unique += 1,
+            2 => self.unique -= 1,
+            _ => {}
+        }
+
+        if self.buffer.len() > self.window {
+            let removed = self.buffer.pop_front().unwrap();
+            self.counts[removed as usize] -= 1;
+            match self.counts[removed as usize] {
+                0 => self.unique -= 1,
+                1 => self.unique += 1,
+                _ => {}
+            }
+        }
+
+        self.window > 0 && self.unique == self.window
+    }
+}
+
+/// Iterate over the characters of a stream, line breaks are ignored
+pub fn stream_bytes<R: Read>(reader: R) -> impl Iterator<Item = std::io::Result<u8>> {
+    BufReader::new(reader)
+        .bytes()
+        .filter(|value| !matches!(value, Ok(b'\n') | Ok(b'\r')))
+}
+
+/// Find the end position of every marker of every given window size in a stream, reading it only
+/// once. Line breaks are ignored.
+pub fn find_markers<R: Read>(reader: R, windows: &[usize]) -> std::io::Result<Vec<Vec<usize>>> {
+    let mut detectors: Vec<_> = windows
+        .iter()
+        .map(|&window| MarkerDetector::new(window))
+        .collect();
+    let mut markers = vec![vec![]; windows.len()];
+
+    for value in stream_bytes(reader) {
+        let value = value?;
+
+        for (detector, markers) in detectors.iter_mut().zip(markers.iter_mut()) {
+            if detector.push(value) {
+                markers.push(detector.position());
+            }
+        }
+    }
+
+    Ok(markers)
+}
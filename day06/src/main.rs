@@ -1,58 +1,75 @@
-use itertools::FoldWhile::{Continue, Done};
-use itertools::Itertools;
-use std::collections::HashSet;
-use std::{env, fs};
-
-fn solve_part_one(data: &str) {
-    let first_char = data.chars().next().unwrap();
-    let res = data
-        .chars()
-        .fold_while(
-            ((first_char, first_char, first_char), 1),
-            |((a, b, c), pos), char| {
-                if char != a && char != b && char != c && c != b && c != a && b != a {
-                    Done(((a, b, c), pos))
-                } else {
-                    Continue(((b, c, char), pos + 1))
-                }
-            },
-        )
-        .into_inner()
-        .1;
-
-    println!("Part one solution: {:#?}", res);
+use std::fs::File;
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use day06::decoder::{Decoder, FrameKind};
+use day06::detector::find_markers;
+
+#[derive(Parser, Debug, Eq, PartialEq, Clone)]
+struct Cli {
+    /// Enable verbose display
+    #[arg(short, long, default_value_t = false)]
+    verbose: bool,
+
+    /// Size of the markers to search for
+    #[arg(short, long, value_delimiter = ',', default_values_t = [4, 14])]
+    windows: Vec<usize>,
+
+    /// Split the datastream into packet and message frames
+    #[arg(short, long, default_value_t = false)]
+    decode: bool,
+
+    /// Report regions without any marker within this distance as corrupted
+    #[arg(short, long)]
+    max_distance: Option<usize>,
+
+    /// File to parse
+    #[arg(default_value = "input")]
+    path: PathBuf,
 }
 
-fn solve_part_two(data: &str) {
-    let res = data
-        .chars()
-        .collect::<Vec<_>>()
-        .windows(14)
-        .enumerate()
-        .filter_map(|(pos, window)| {
-            if window.iter().collect::<HashSet<_>>().len() == 14 {
-                Some(pos + 14)
-            } else {
-                None
-            }
-        })
-        .next()
-        .unwrap();
-
-    println!("Part two solution: {:#?}", res);
+fn decode(path: &PathBuf, max_distance: Option<usize>) {
+    let decoder = Decoder {
+        max_distance,
+        ..Decoder::default()
+    };
+    let decoded = decoder.decode(File::open(path).unwrap()).unwrap();
+
+    for frame in &decoded.frames {
+        let kind = match frame.kind {
+            FrameKind::Packet => "packet",
+            FrameKind::Message => "message",
+        };
+        println!(
+            "{} frame: marker {:?}, payload {:?}",
+            kind, frame.marker, frame.payload
+        );
+    }
+
+    for corruption in &decoded.corruptions {
+        println!("Corrupted region: {:?}", corruption);
+    }
 }
 
 fn main() {
-    let args: Vec<_> = env::args().collect();
+    let args = Cli::parse();
 
-    let file_path = if args.len() > 1 {
-        args[1].clone()
-    } else {
-        "input".to_string()
-    };
+    let file = File::open(&args.path).unwrap();
+    let markers = find_markers(file, &args.windows).unwrap();
+
+    for (window, markers) in args.windows.iter().zip(markers) {
+        match markers.first() {
+            Some(first) => println!("First marker of size {}: {:#?}", window, first),
+            None => println!("No marker of size {}", window),
+        }
 
-    let data = fs::read_to_string(&file_path).unwrap();
+        if args.verbose {
+            println!("Every marker of size {}: {:?}", window, markers);
+        }
+    }
 
-    solve_part_one(&data);
-    solve_part_two(&data);
+    if args.decode {
+        decode(&args.path, args.max_distance);
+    }
 }
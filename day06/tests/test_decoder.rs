@@ -0,0 +1,77 @@
+use day06::decoder::{Decoded, Decoder, Frame, FrameKind};
+
+fn decoder(max_distance: Option<usize>) -> Decoder {
+    Decoder {
+        packet_window: 2,
+        message_window: 3,
+        max_distance,
+    }
+}
+
+#[test]
+fn test_decode_frames() {
+    assert_eq!(
+        decoder(None).decode("aabbxabcyyyzzz\n".as_bytes()).unwrap(),
+        Decoded {
+            frames: vec![
+                Frame {
+                    kind: FrameKind::Packet,
+                    marker: 1..3,
+                    payload: 3..3,
+                },
+                Frame {
+                    kind: FrameKind::Message,
+                    marker: 3..6,
+                    payload: 6..6,
+                },
+                Frame {
+                    kind: FrameKind::Message,
+                    marker: 6..9,
+                    payload: 9..14,
+                },
+            ],
+            corruptions: vec![],
+        }
+    );
+}
+
+#[test]
+fn test_decode_corruption() {
+    let decoded = decoder(Some(4))
+        .decode("aaaaaaabxyzxxxxxxxxxab".as_bytes())
+        .unwrap();
+
+    assert_eq!(
+        decoded.frames,
+        vec![
+            Frame {
+                kind: FrameKind::Packet,
+                marker: 6..8,
+                payload: 8..8,
+            },
+            Frame {
+                kind: FrameKind::Message,
+                marker: 8..11,
+                payload: 11..16,
+            },
+            Frame {
+                kind: FrameKind::Packet,
+                marker: 19..21,
+                payload: 21..22,
+            },
+        ]
+    );
+    assert_eq!(decoded.corruptions, vec![0..6, 11..19]);
+}
+
+#[test]
+fn test_decode_example() {
+    let decoded = Decoder::default()
+        .decode("mjqjpqmgbljsphdztnvjfqwrcgsmlb".as_bytes())
+        .unwrap();
+
+    // The start-of-message marker cannot overlap the start-of-packet marker
+    assert_eq!(decoded.frames[0].marker, 3..7);
+    assert_eq!(decoded.frames[1].marker, 11..25);
+    assert_eq!(decoded.frames.len(), 2);
+}
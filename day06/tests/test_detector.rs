@@ -0,0 +1,37 @@
+use day06::detector::{find_markers, MarkerDetector};
+
+#[test]
+fn test_first_markers() {
+    for (data, first_packet, first_message) in [
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb\n", 7, 19),
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz\n", 5, 23),
+        ("nppdvjthqldpwncqszvftbrmjlhg\n", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg\n", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw\n", 11, 26),
+    ] {
+        let markers = find_markers(data.as_bytes(), &[4, 14]).unwrap();
+        assert_eq!(markers[0][0], first_packet);
+        assert_eq!(markers[1][0], first_message);
+    }
+}
+
+#[test]
+fn test_every_marker() {
+    assert_eq!(
+        find_markers("aabcdbb\n".as_bytes(), &[1, 3, 4]).unwrap(),
+        vec![vec![1, 2, 3, 4, 5, 6, 7], vec![4, 5, 6], vec![5]]
+    );
+}
+
+#[test]
+fn test_detector() {
+    let mut detector = MarkerDetector::new(3);
+
+    assert!(!detector.push(b'a'));
+    assert!(!detector.push(b'b'));
+    assert!(!detector.push(b'b'));
+    assert!(!detector.push(b'c'));
+    assert!(detector.push(b'a'));
+    assert!(!detector.push(b'a'));
+    assert_eq!(detector.position(), 6);
+}
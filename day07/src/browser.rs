@@ -0,0 +1,378 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{
+    self, disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use crossterm::{cursor, execute, queue};
+use id_tree::{NodeId, Tree};
+
+use crate::model::Inode;
+use crate::owned::format_size;
+use crate::tree::node_path;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum SortBy {
+    Size,
+    Name,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Action {
+    Up,
+    Down,
+    Enter,
+    Leave,
+    Sort(SortBy),
+    ToggleMark,
+    Quit,
+}
+
+/// A child of the directory being browsed
+#[derive(Debug, PartialEq, Clone)]
+pub struct BrowserEntry {
+    pub node_id: NodeId,
+    pub name: String,
+    pub size: i64,
+    pub is_dir: bool,
+    /// Share of the size of the browsed directory
+    pub percent: f64,
+    pub marked: bool,
+}
+
+/// State of an ncdu-like browser of a tree whose directory sizes are computed. It lets the
+/// user mark files and directories for deletion and shows the resulting free space.
+pub struct Browser<'t, 'a> {
+    tree: &'t Tree<Inode<'a>>,
+    current: NodeId,
+    selected: usize,
+    sort: SortBy,
+    marked: HashSet<NodeId>,
+    disk_size: i64,
+    required_space: i64,
+}
+
+impl<'t, 'a> Browser<'t, 'a> {
+    pub fn new(tree: &'t Tree<Inode<'a>>, disk_size: i64, required_space: i64) -> Self {
+        Self {
+            tree,
+            current: tree.root_node_id().unwrap().clone(),
+            selected: 0,
+            sort: SortBy::Size,
+            marked: HashSet::new(),
+            disk_size,
+            required_space,
+        }
+    }
+
+    fn size(&self, node_id: &NodeId) -> i64 {
+        self.tree.get(node_id).unwrap().data().size()
+    }
+
+    pub fn current_path(&self) -> String {
+        node_path(self.tree, &self.current)
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Children of the browsed directory, in display order
+    pub fn entries(&self) -> Vec<BrowserEntry> {
+        let current_size = self.size(&self.current);
+
+        let mut entries: Vec<BrowserEntry> = self
+            .tree
+            .children_ids(&self.current)
+            .unwrap()
+            .map(|child_id| {
+                let inode = self.tree.get(child_id).unwrap().data();
+                BrowserEntry {
+                    node_id: child_id.clone(),
+                    name: inode.name().to_string(),
+                    size: inode.size(),
+                    is_dir: matches!(inode, Inode::Dir(_)),
+                    percent: match current_size {
+                        0 => 0.0,
+                        _ => 100.0 * inode.size() as f64 / current_size as f64,
+                    },
+                    marked: self.is_marked(child_id),
+                }
+            })
+            .collect();
+
+        match self.sort {
+            SortBy::Size => entries.sort_by(|left, right| {
+                right
+                    .size
+                    .cmp(&left.size)
+                    .then_with(|| left.name.cmp(&right.name))
+            }),
+            SortBy::Name => entries.sort_by(|left, right| left.name.cmp(&right.name)),
+        }
+
+        entries
+    }
+
+    /// A node is marked if it or one of its ancestors is marked
+    pub fn is_marked(&self, node_id: &NodeId) -> bool {
+        self.marked.contains(node_id)
+            || self
+                .tree
+                .ancestor_ids(node_id)
+                .unwrap()
+                .any(|ancestor_id| self.marked.contains(ancestor_id))
+    }
+
+    /// Number of bytes freed by deleting the marked nodes
+    pub fn marked_size(&self) -> i64 {
+        self.marked
+            .iter()
+            .filter(|node_id| {
+                !self
+                    .tree
+                    .ancestor_ids(node_id)
+                    .unwrap()
+                    .any(|ancestor_id| self.marked.contains(ancestor_id))
+            })
+            .map(|node_id| self.size(node_id))
+            .sum()
+    }
+
+    /// Free space once the marked nodes are deleted
+    pub fn free_space(&self) -> i64 {
+        self.disk_size - self.size(self.tree.root_node_id().unwrap()) + self.marked_size()
+    }
+
+    pub fn has_enough_space(&self) -> bool {
+        self.free_space() >= self.required_space
+    }
+
+    /// Unmark a node only marked through an ancestor: the ancestor is unmarked and the rest of its
+    /// content is marked instead
+    fn unmark_from_ancestor(&mut self, node_id: &NodeId, marked_ancestor: &NodeId) {
+        self.marked.remove(marked_ancestor);
+
+        let mut current = node_id.clone();
+        while &current != marked_ancestor {
+            let parent = self.tree.get(&current).unwrap().parent().unwrap().clone();
+            for sibling_id in self.tree.children_ids(&parent).unwrap() {
+                if sibling_id != &current {
+                    self.marked.insert(sibling_id.clone());
+                }
+            }
+            current = parent;
+        }
+    }
+
+    /// Apply an action, return `false` if the browser must be closed
+    pub fn apply(&mut self, action: Action) -> bool {
+        let entries = self.entries();
+        match action {
+            Action::Up => self.selected = self.selected.saturating_sub(1),
+            Action::Down => {
+                if self.selected + 1 < entries.len() {
+                    self.selected += 1;
+                }
+            }
+            Action::Enter => {
+                if let Some(entry) = entries.get(self.selected) {
+                    if entry.is_dir {
+                        self.current = entry.node_id.clone();
+                        self.selected = 0;
+                    }
+                }
+            }
+            Action::Leave => {
+                if let Some(parent) = self.tree.get(&self.current).unwrap().parent() {
+                    let previous = self.current.clone();
+                    self.current = parent.clone();
+                    // Select the directory we come from
+                    self.selected = self
+                        .entries()
+                        .iter()
+                        .position(|entry| entry.node_id == previous)
+                        .unwrap_or(0);
+                }
+            }
+            Action::Sort(sort) => {
+                let selected = entries.get(self.selected).map(|entry| &entry.node_id);
+                self.sort = sort;
+                self.selected = self
+                    .entries()
+                    .iter()
+                    .position(|entry| Some(&entry.node_id) == selected)
+                    .unwrap_or(0);
+            }
+            Action::ToggleMark => {
+                if let Some(entry) = entries.get(self.selected) {
+                    let marked_ancestor = self
+                        .tree
+                        .ancestor_ids(&entry.node_id)
+                        .unwrap()
+                        .find(|ancestor_id| self.marked.contains(ancestor_id))
+                        .cloned();
+
+                    if self.marked.remove(&entry.node_id) {
+                        // Unmarked
+                    } else if let Some(marked_ancestor) = marked_ancestor {
+                        self.unmark_from_ancestor(&entry.node_id, &marked_ancestor);
+                    } else {
+                        // Descendants are deleted with the node, no need to keep them marked
+                        let descendants: Vec<NodeId> = self
+                            .tree
+                            .traverse_pre_order_ids(&entry.node_id)
+                            .unwrap()
+                            .collect();
+                        for descendant in descendants {
+                            self.marked.remove(&descendant);
+                        }
+                        self.marked.insert(entry.node_id.clone());
+                    }
+                }
+            }
+            Action::Quit => return false,
+        }
+
+        true
+    }
+
+    /// Render the browser in lines of at most `width` characters, using `height` lines
+    pub fn render(&self, width: usize, height: usize) -> Vec<String> {
+        let mut lines = vec![
+            format!(
+                "--- {} --- sorted by {}",
+                self.current_path(),
+                match self.sort {
+                    SortBy::Size => "size",
+                    SortBy::Name => "name",
+                }
+            ),
+            format!(
+                "Free space: {} / {} required, {} marked for deletion: {}",
+                format_size(self.free_space(), true),
+                format_size(self.required_space, true),
+                format_size(self.marked_size(), true),
+                if self.has_enough_space() {
+                    "enough"
+                } else {
+                    "not enough"
+                }
+            ),
+        ];
+
+        // Keep the selected entry visible
+        let entries = self.entries();
+        let visible = height.saturating_sub(lines.len() + 1).max(1);
+        let first = self.selected.saturating_sub(visible - 1);
+
+        for (index, entry) in entries.iter().enumerate().skip(first).take(visible) {
+            let bar_length = (entry.percent / 10.0).round() as usize;
+            lines.push(format!(
+                "{}{} {:>7} {:>5.1}% [{:<10}] {}{}",
+                if index == self.selected { ">" } else { " " },
+                if entry.marked { "*" } else { " " },
+                format_size(entry.size, true),
+                entry.percent,
+                "#".repeat(bar_length.min(10)),
+                entry.name,
+                if entry.is_dir { "/" } else { "" }
+            ));
+        }
+
+        lines.push(
+            "up/down: move  enter/right: open  left: back  s/n: sort  space: mark  q: quit"
+                .to_string(),
+        );
+
+        lines
+            .into_iter()
+            .map(|line| line.chars().take(width).collect())
+            .collect()
+    }
+}
+
+fn action_from_key(code: KeyCode) -> Option<Action> {
+    match code {
+        KeyCode::Up | KeyCode::Char('k') => Some(Action::Up),
+        KeyCode::Down | KeyCode::Char('j') => Some(Action::Down),
+        KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => Some(Action::Enter),
+        KeyCode::Left | KeyCode::Backspace | KeyCode::Char('h') => Some(Action::Leave),
+        KeyCode::Char('s') => Some(Action::Sort(SortBy::Size)),
+        KeyCode::Char('n') => Some(Action::Sort(SortBy::Name)),
+        KeyCode::Char(' ') | KeyCode::Char('d') => Some(Action::ToggleMark),
+        KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+        _ => None,
+    }
+}
+
+fn run_loop(browser: &mut Browser, stdout: &mut io::Stdout) -> io::Result<()> {
+    loop {
+        // Some terminals do not report their size
+        let (width, height) = match terminal::size()? {
+            (0, _) | (_, 0) => (80, 24),
+            size => size,
+        };
+        queue!(
+            stdout,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0)
+        )?;
+        for (index, line) in browser
+            .render(width as usize, height as usize)
+            .iter()
+            .enumerate()
+        {
+            let highlighted = index >= 2 && line.starts_with('>');
+            queue!(stdout, cursor::MoveTo(0, index as u16))?;
+            if highlighted {
+                queue!(stdout, SetAttribute(Attribute::Reverse))?;
+            }
+            queue!(stdout, Print(line), SetAttribute(Attribute::Reset))?;
+        }
+        stdout.flush()?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Some(action) = action_from_key(key.code) {
+                if !browser.apply(action) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Restore the terminal when dropped, even if the browser panics
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new(stdout: &mut io::Stdout) -> io::Result<Self> {
+        enable_raw_mode()?;
+        // Created before entering the alternate screen so that raw mode is disabled on failure
+        let guard = TerminalGuard;
+        execute!(stdout, EnterAlternateScreen, cursor::Hide)?;
+
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // Nothing more can be done if the terminal cannot be restored
+        let _ = execute!(io::stdout(), cursor::Show, LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
+
+/// Run the browser in the terminal until the user quits
+pub fn browse(browser: &mut Browser) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let _guard = TerminalGuard::new(&mut stdout)?;
+
+    run_loop(browser, &mut stdout)
+}
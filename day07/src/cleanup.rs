@@ -0,0 +1,426 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+use id_tree::{NodeId, Tree};
+
+use crate::model::Inode;
+use crate::tree::node_path;
+
+/// A set of files and directories to delete
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Plan {
+    pub paths: Vec<String>,
+    /// Number of bytes deleted
+    pub cost: i64,
+    /// Free space once the plan is applied
+    pub free_space: i64,
+}
+
+/// Why no optimal plan was found
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum CleanupError {
+    /// Deleting every file does not free enough space
+    NotEnoughFiles { to_delete: i64, total: i64 },
+    /// The files are too big for the table and the search visited more than `limit` nodes
+    SearchLimit { limit: usize },
+}
+
+impl Display for CleanupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CleanupError::NotEnoughFiles { to_delete, total } => write!(
+                f,
+                "{} bytes must be deleted but the files only hold {} bytes",
+                to_delete, total
+            ),
+            CleanupError::SearchLimit { limit } => {
+                write!(f, "no optimal plan found after {} search steps", limit)
+            }
+        }
+    }
+}
+
+/// Largest sum of file sizes handled with a table, in blocks of the greatest common divisor of
+/// the sizes. Every table then holds at most 16 MiB.
+pub const TABLE_LIMIT: usize = 1 << 27;
+
+/// Default number of nodes visited by the search when the sizes do not fit in a table
+pub const DEFAULT_SEARCH_LIMIT: usize = 10_000_000;
+
+/// Find ways to free at least `required_space` bytes on a disk of `disk_size` bytes.
+pub struct CleanupPlanner<'t, 'a> {
+    tree: &'t Tree<Inode<'a>>,
+    disk_size: i64,
+    required_space: i64,
+    search_limit: usize,
+}
+
+impl<'t, 'a> CleanupPlanner<'t, 'a> {
+    pub fn new(tree: &'t Tree<Inode<'a>>, disk_size: i64, required_space: i64) -> Self {
+        Self {
+            tree,
+            disk_size,
+            required_space,
+            search_limit: DEFAULT_SEARCH_LIMIT,
+        }
+    }
+
+    /// Number of nodes visited by the search before giving up, when the sizes are too big for a
+    /// table
+    pub fn with_search_limit(self, search_limit: usize) -> Self {
+        Self {
+            search_limit,
+            ..self
+        }
+    }
+
+    fn root(&self) -> &'t NodeId {
+        self.tree.root_node_id().unwrap()
+    }
+
+    fn used_space(&self) -> i64 {
+        self.tree.get(self.root()).unwrap().data().size()
+    }
+
+    /// Number of bytes that must be deleted
+    pub fn to_delete(&self) -> i64 {
+        (self.required_space - self.disk_size + self.used_space()).max(0)
+    }
+
+    fn plan(&self, node_ids: &[NodeId]) -> Plan {
+        let cost = node_ids
+            .iter()
+            .map(|node_id| self.tree.get(node_id).unwrap().data().size())
+            .sum();
+
+        Plan {
+            paths: node_ids
+                .iter()
+                .map(|node_id| node_path(self.tree, node_id))
+                .collect(),
+            cost,
+            free_space: self.disk_size - self.used_space() + cost,
+        }
+    }
+
+    /// Find the set of non-nested files and directories freeing enough space with the smallest
+    /// number of deleted bytes.
+    ///
+    /// Deleting a directory frees exactly the size of the files it contains, so the reachable
+    /// costs are the subset sums of the file sizes. The chosen files are then grouped into the
+    /// directories they fill completely.
+    pub fn optimal_plan(&self) -> Result<Plan, CleanupError> {
+        let to_delete = self.to_delete();
+        if to_delete == 0 {
+            return Ok(self.plan(&[]));
+        }
+
+        let files: Vec<(NodeId, usize)> = self
+            .tree
+            .traverse_pre_order_ids(self.root())
+            .unwrap()
+            .filter_map(|node_id| match self.tree.get(&node_id).unwrap().data() {
+                Inode::File(file_entry) => Some((node_id, file_entry.size as usize)),
+                Inode::Dir(_) => None,
+            })
+            .collect();
+
+        let sizes: Vec<usize> = files.iter().map(|(_, size)| *size).collect();
+        let chosen = smallest_subset_sum(&sizes, to_delete as usize, self.search_limit)?;
+
+        let chosen: HashSet<NodeId> = files
+            .into_iter()
+            .zip(chosen)
+            .filter(|(_, chosen)| *chosen)
+            .map(|((node_id, _), _)| node_id)
+            .collect();
+
+        Ok(self.plan(&self.group_by_directory(&chosen)))
+    }
+
+    /// Replace files by their directory whenever every file of a directory is chosen
+    fn group_by_directory(&self, files: &HashSet<NodeId>) -> Vec<NodeId> {
+        let mut grouped = vec![];
+        self.group_by_directory_inner(self.root(), files, &mut grouped);
+
+        grouped
+    }
+
+    /// Return whether every file under the node is chosen and whether there is any file under
+    /// it. Nodes that are not fully chosen add their fully chosen children to `grouped`.
+    fn group_by_directory_inner(
+        &self,
+        node_id: &NodeId,
+        files: &HashSet<NodeId>,
+        grouped: &mut Vec<NodeId>,
+    ) -> (bool, bool) {
+        match self.tree.get(node_id).unwrap().data() {
+            Inode::File(_) => (files.contains(node_id), true),
+            Inode::Dir(_) => {
+                let mut children = vec![];
+                let mut full = true;
+                let mut any = false;
+                for child_id in self.tree.children_ids(node_id).unwrap() {
+                    let (child_full, child_any) =
+                        self.group_by_directory_inner(child_id, files, &mut children);
+                    any |= child_any;
+                    if !child_full {
+                        full = false;
+                    } else if child_any {
+                        children.push(child_id.clone());
+                    }
+                }
+
+                // The root cannot be deleted
+                if full && node_id != self.root() {
+                    (true, any)
+                } else {
+                    grouped.extend(children);
+                    (false, any)
+                }
+            }
+        }
+    }
+
+    /// List the plans freeing enough space, cheapest first: the optimal plan followed by the
+    /// single files and directories big enough to be deleted alone. The error of the optimal plan
+    /// search is returned along with the other plans when it fails.
+    pub fn candidates(&self, count: usize) -> (Vec<Plan>, Option<CleanupError>) {
+        let to_delete = self.to_delete();
+
+        let mut singles: Vec<(i64, NodeId)> = self
+            .tree
+            .traverse_pre_order_ids(self.root())
+            .unwrap()
+            .filter(|node_id| node_id != self.root())
+            .map(|node_id| (self.tree.get(&node_id).unwrap().data().size(), node_id))
+            .filter(|(size, _)| *size >= to_delete)
+            .collect();
+        singles.sort_by_key(|(size, _)| *size);
+
+        let (optimal, error) = match self.optimal_plan() {
+            Ok(plan) => (Some(plan), None),
+            Err(error) => (None, Some(error)),
+        };
+        let plans = optimal
+            .clone()
+            .into_iter()
+            .chain(
+                singles
+                    .into_iter()
+                    .map(|(_, node_id)| self.plan(&[node_id]))
+                    .filter(|plan| Some(plan) != optimal.as_ref()),
+            )
+            .take(count)
+            .collect();
+
+        (plans, error)
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Choose the sizes whose sum is the smallest one reaching `target`. Memory stays proportional
+/// to the number of sizes or bounded by `TABLE_LIMIT`, whatever the sizes.
+fn smallest_subset_sum(
+    sizes: &[usize],
+    target: usize,
+    search_limit: usize,
+) -> Result<Vec<bool>, CleanupError> {
+    let total: usize = sizes.iter().sum();
+    if total < target {
+        return Err(CleanupError::NotEnoughFiles {
+            to_delete: target as i64,
+            total: total as i64,
+        });
+    }
+
+    // Every sum is a multiple of the common divisor, so the sizes can be counted in blocks of it
+    // without losing any solution
+    let block = sizes.iter().fold(0, |block, size| gcd(block, *size)).max(1);
+    let blocks: Vec<usize> = sizes.iter().map(|size| size / block).collect();
+    let target = target.div_ceil(block);
+
+    if total / block <= TABLE_LIMIT {
+        Ok(table_subset_sum(&blocks, target))
+    } else {
+        search_subset_sum(&blocks, target, search_limit)
+    }
+}
+
+/// Subset sums with a bitset knapsack, the chosen sizes are rebuilt by splitting the sizes in
+/// halves so that only a few bitsets are alive at once
+fn table_subset_sum(sizes: &[usize], target: usize) -> Vec<bool> {
+    let total: usize = sizes.iter().sum();
+    let reachable = reachable_sums(sizes, total);
+    let cost = (target..=total)
+        .find(|&cost| reachable.contains(cost))
+        .unwrap();
+    drop(reachable);
+
+    let mut chosen = vec![false; sizes.len()];
+    choose_sum(sizes, cost, &mut chosen);
+
+    chosen
+}
+
+/// Set of the subset sums of the sizes up to `max`
+fn reachable_sums(sizes: &[usize], max: usize) -> BitSet {
+    let mut reachable = BitSet::new(max + 1);
+    reachable.insert(0);
+    for size in sizes {
+        reachable.shift_or(*size);
+    }
+
+    reachable
+}
+
+/// Mark sizes adding up to `sum`, which must be reachable
+fn choose_sum(sizes: &[usize], sum: usize, chosen: &mut [bool]) {
+    if sum == 0 {
+        return;
+    }
+    if sizes.len() == 1 {
+        chosen[0] = true;
+        return;
+    }
+
+    let middle = sizes.len() / 2;
+    let (left, right) = sizes.split_at(middle);
+    let left_sum = {
+        let left_reachable = reachable_sums(left, sum);
+        let right_reachable = reachable_sums(right, sum);
+        (0..=sum)
+            .find(|&left_sum| {
+                left_reachable.contains(left_sum) && right_reachable.contains(sum - left_sum)
+            })
+            .unwrap()
+    };
+
+    let (left_chosen, right_chosen) = chosen.split_at_mut(middle);
+    choose_sum(left, left_sum, left_chosen);
+    choose_sum(right, sum - left_sum, right_chosen);
+}
+
+/// Depth-first branch and bound over the sizes, biggest first, giving up after `search_limit`
+/// nodes
+fn search_subset_sum(
+    sizes: &[usize],
+    target: usize,
+    search_limit: usize,
+) -> Result<Vec<bool>, CleanupError> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|index| std::cmp::Reverse(sizes[*index]));
+    let sorted: Vec<usize> = order.iter().map(|index| sizes[*index]).collect();
+
+    // Sum of the sizes from an index to the end
+    let mut remaining = vec![0; sorted.len() + 1];
+    for index in (0..sorted.len()).rev() {
+        remaining[index] = remaining[index + 1] + sorted[index];
+    }
+    let smallest = sorted.last().copied().unwrap_or(0);
+
+    // Every node is an index, the current sum and the number of chosen sizes, the chosen sizes
+    // being the first ones of `path`
+    let mut best: Option<(usize, Vec<usize>)> = None;
+    let mut path = vec![];
+    let mut stack = vec![(0, 0, 0)];
+    let mut visited = 0;
+    while let Some((index, sum, depth)) = stack.pop() {
+        visited += 1;
+        if visited > search_limit {
+            return Err(CleanupError::SearchLimit {
+                limit: search_limit,
+            });
+        }
+        path.truncate(depth);
+
+        if sum >= target {
+            if best.as_ref().is_none_or(|(best_sum, _)| sum < *best_sum) {
+                best = Some((sum, path.clone()));
+                if sum == target {
+                    break;
+                }
+            }
+            continue;
+        }
+        if sum + remaining[index] < target {
+            continue;
+        }
+        if best
+            .as_ref()
+            .is_some_and(|(best_sum, _)| sum + smallest >= *best_sum)
+        {
+            continue;
+        }
+
+        // Skipping a size skips every following equal size, so that equal sizes are only chosen
+        // in one order
+        let mut next = index + 1;
+        while next < sorted.len() && sorted[next] == sorted[index] {
+            next += 1;
+        }
+        stack.push((next, sum, depth));
+        // Explored first
+        path.push(index);
+        stack.push((index + 1, sum + sorted[index], depth + 1));
+    }
+
+    let (_, path) = best.unwrap();
+    let mut chosen = vec![false; sizes.len()];
+    for index in path {
+        chosen[order[index]] = true;
+    }
+
+    Ok(chosen)
+}
+
+/// Fixed size set of integers
+struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    fn insert(&mut self, value: usize) {
+        self.words[value / 64] |= 1 << (value % 64);
+    }
+
+    fn contains(&self, value: usize) -> bool {
+        value < self.len && self.words[value / 64] & (1 << (value % 64)) != 0
+    }
+
+    /// Add `value + shift` for every value of the set, values past the end of the set are
+    /// dropped.
+    fn shift_or(&mut self, shift: usize) {
+        let word_shift = shift / 64;
+        let bit_shift = shift % 64;
+
+        // Go from the end so that the source words are not modified yet
+        for index in (word_shift..self.words.len()).rev() {
+            let source = index - word_shift;
+            let mut shifted = self.words[source] << bit_shift;
+            if bit_shift > 0 && source > 0 {
+                shifted |= self.words[source - 1] >> (64 - bit_shift);
+            }
+            if index == self.words.len() - 1 && !self.len.is_multiple_of(64) {
+                shifted &= (1 << (self.len % 64)) - 1;
+            }
+
+            self.words[index] |= shifted;
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use id_tree::InsertBehavior::{AsRoot, UnderNode};
+use id_tree::{Node, NodeId, Tree, TreeBuilder};
+
+use crate::model::{DirEntry, FileEntry, Inode};
+use crate::tree::compute_tree_dir_size;
+
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct ImportOptions {
+    /// Follow symbolic links instead of skipping them
+    pub follow_symlinks: bool,
+    /// Import files and directories whose name starts with a dot
+    pub include_hidden: bool,
+    /// Import unreadable directories as empty directories and leave out the entries that cannot be
+    /// read instead of failing
+    pub skip_unreadable: bool,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum ImportedEntry {
+    Dir {
+        name: String,
+        children: Vec<ImportedEntry>,
+    },
+    File {
+        name: String,
+        size: i64,
+    },
+}
+
+/// Directory imported from the disk. The inodes of the tree borrow their names from it, in the
+/// same way the parsed inodes borrow them from the input.
+#[derive(Debug)]
+pub struct Import {
+    root: ImportedEntry,
+    /// Paths that could not be read, only filled when unreadable entries are skipped
+    pub errors: Vec<(PathBuf, io::Error)>,
+}
+
+impl Import {
+    pub fn from_path(path: &Path, options: &ImportOptions) -> io::Result<Self> {
+        let mut importer = Importer {
+            options,
+            errors: vec![],
+            visited: HashSet::new(),
+        };
+        let children = importer.import_dir(path)?;
+
+        Ok(Self {
+            root: ImportedEntry::Dir {
+                name: "/".to_string(),
+                children,
+            },
+            errors: importer.errors,
+        })
+    }
+
+    /// Build the tree of the imported directory, with the size of every directory computed
+    pub fn tree(&self) -> Tree<Inode<'_>> {
+        let mut tree: Tree<Inode> = TreeBuilder::new().build();
+        insert_entry(&mut tree, &self.root, None);
+
+        compute_tree_dir_size(tree)
+    }
+}
+
+fn insert_entry<'a>(tree: &mut Tree<Inode<'a>>, entry: &'a ImportedEntry, parent: Option<&NodeId>) {
+    let inode = match entry {
+        ImportedEntry::Dir { name, .. } => Inode::Dir(DirEntry { name, size: 0 }),
+        ImportedEntry::File { name, size } => Inode::File(FileEntry { name, size: *size }),
+    };
+    let behavior = match parent {
+        None => AsRoot,
+        Some(parent) => UnderNode(parent),
+    };
+    let node_id = tree.insert(Node::new(inode), behavior).unwrap();
+
+    if let ImportedEntry::Dir { children, .. } = entry {
+        for child in children {
+            insert_entry(tree, child, Some(&node_id));
+        }
+    }
+}
+
+struct Importer<'o> {
+    options: &'o ImportOptions,
+    errors: Vec<(PathBuf, io::Error)>,
+    /// Canonical paths of the directories being imported, to avoid symbolic link loops
+    visited: HashSet<PathBuf>,
+}
+
+impl Importer<'_> {
+    /// Record the error if unreadable entries are skipped, otherwise return it
+    fn skip(&mut self, path: &Path, error: io::Error) -> io::Result<()> {
+        if self.options.skip_unreadable {
+            self.errors.push((path.to_path_buf(), error));
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    fn import_dir(&mut self, path: &Path) -> io::Result<Vec<ImportedEntry>> {
+        let canonical = match fs::canonicalize(path) {
+            Ok(canonical) => canonical,
+            Err(error) => {
+                self.skip(path, error)?;
+                return Ok(vec![]);
+            }
+        };
+        if !self.visited.insert(canonical.clone()) {
+            return Ok(vec![]);
+        }
+
+        let children = match self.read_dir(path) {
+            Ok(children) => children,
+            Err(error) => {
+                self.skip(path, error)?;
+                vec![]
+            }
+        };
+
+        self.visited.remove(&canonical);
+
+        Ok(children)
+    }
+
+    fn read_dir(&mut self, path: &Path) -> io::Result<Vec<ImportedEntry>> {
+        let mut dir_entries = vec![];
+        for dir_entry in fs::read_dir(path)? {
+            match dir_entry {
+                Ok(dir_entry) => dir_entries.push(dir_entry),
+                Err(error) => self.skip(path, error)?,
+            }
+        }
+        dir_entries.sort_by_key(|dir_entry| dir_entry.file_name());
+
+        let mut children = vec![];
+        for dir_entry in dir_entries {
+            let name = dir_entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') && !self.options.include_hidden {
+                continue;
+            }
+
+            let child_path = dir_entry.path();
+            // The entry may have been deleted since the directory was listed
+            let mut file_type = match dir_entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(error) => {
+                    self.skip(&child_path, error)?;
+                    continue;
+                }
+            };
+            if file_type.is_symlink() {
+                if !self.options.follow_symlinks {
+                    continue;
+                }
+                match fs::metadata(&child_path) {
+                    Ok(metadata) => file_type = metadata.file_type(),
+                    // Dangling link
+                    Err(_) => continue,
+                }
+            }
+
+            if file_type.is_dir() {
+                children.push(ImportedEntry::Dir {
+                    children: self.import_dir(&child_path)?,
+                    name,
+                });
+            } else {
+                match fs::metadata(&child_path) {
+                    Ok(metadata) => children.push(ImportedEntry::File {
+                        size: metadata.len() as i64,
+                        name,
+                    }),
+                    Err(error) => self.skip(&child_path, error)?,
+                }
+            }
+        }
+
+        Ok(children)
+    }
+}
@@ -0,0 +1,456 @@
+use id_tree::InsertBehavior::{AsRoot, UnderNode};
+use id_tree::MoveBehavior::ToParent;
+use id_tree::RemoveBehavior::DropChildren;
+use id_tree::{Node, NodeId, Tree, TreeBuilder};
+
+use crate::model::{CdTarget, Command, DirEntry, Inode, SizeFilter};
+use crate::tree::{compute_tree_dir_size, node_path};
+
+/// Something in the transcript that does not match the state of the emulated filesystem. The
+/// command is the index of the command in the transcript.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Inconsistency {
+    /// `cd` targets a directory that does not exist
+    UnknownDirectory { command: usize, path: String },
+    /// `rm` or `mv` targets a path that does not exist
+    UnknownPath { command: usize, path: String },
+    /// `mkdir` or `mv` creates something that already exists
+    AlreadyExists { command: usize, path: String },
+    /// `rm` without `-r` targets a directory
+    IsADirectory { command: usize, path: String },
+    /// `ls` reports a different size for a known file, the new size is kept
+    ConflictingSize {
+        command: usize,
+        path: String,
+        previous: i64,
+        new: i64,
+    },
+    /// `ls` reports a file where a directory is known, or the opposite, the known entry is kept
+    ConflictingType { command: usize, path: String },
+    /// The output found in the transcript is not the one of the emulated command
+    OutputMismatch {
+        command: usize,
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+}
+
+/// Emulated filesystem built by replaying a terminal session
+pub struct FileSystem<'a> {
+    tree: Tree<Inode<'a>>,
+    current: NodeId,
+    inconsistencies: Vec<Inconsistency>,
+}
+
+impl<'a> Default for FileSystem<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> FileSystem<'a> {
+    pub fn new() -> Self {
+        let mut tree: Tree<Inode> = TreeBuilder::new().build();
+        let root = tree
+            .insert(
+                Node::new(Inode::Dir(DirEntry { name: "/", size: 0 })),
+                AsRoot,
+            )
+            .unwrap();
+
+        Self {
+            tree,
+            current: root,
+            inconsistencies: vec![],
+        }
+    }
+
+    pub fn inconsistencies(&self) -> &[Inconsistency] {
+        &self.inconsistencies
+    }
+
+    /// Get the tree with the size of every directory computed
+    pub fn into_tree(self) -> Tree<Inode<'a>> {
+        compute_tree_dir_size(self.tree)
+    }
+
+    pub fn replay(&mut self, commands: &[Command<'a>]) {
+        for (index, command) in commands.iter().enumerate() {
+            self.execute(index, command);
+        }
+    }
+
+    /// Execute a command and return its output
+    pub fn execute(&mut self, index: usize, command: &Command<'a>) -> Vec<String> {
+        match command {
+            Command::Ls(ls_lines) => {
+                for ls_line in ls_lines {
+                    self.observe(index, ls_line);
+                }
+                vec![]
+            }
+            Command::Cd(cd_target) => {
+                match cd_target {
+                    CdTarget::Root => self.current = self.root(),
+                    CdTarget::Up => {
+                        if let Some(parent) = self.tree.get(&self.current).unwrap().parent() {
+                            self.current = parent.clone();
+                        }
+                    }
+                    CdTarget::Directory(path) => match self.resolve(path) {
+                        Some(node_id) if self.is_dir(&node_id) => self.current = node_id,
+                        _ => self.inconsistencies.push(Inconsistency::UnknownDirectory {
+                            command: index,
+                            path: path.to_string(),
+                        }),
+                    },
+                }
+                vec![]
+            }
+            Command::Pwd(output) => {
+                let actual = vec![self.path(&self.current)];
+                self.check_output(index, output, actual)
+            }
+            Command::Mkdir(path) => {
+                self.mkdir(index, path);
+                vec![]
+            }
+            Command::Rm { path, recursive } => {
+                self.rm(index, path, *recursive);
+                vec![]
+            }
+            Command::Mv { src, dst } => {
+                self.mv(index, src, dst);
+                vec![]
+            }
+            Command::Du { path, output } => {
+                let actual = match self.resolve_or_current(index, *path) {
+                    Some(node_id) => {
+                        let mut lines = vec![];
+                        self.du(&node_id, &mut lines);
+                        lines
+                    }
+                    None => vec![],
+                };
+                self.check_output(index, output, actual)
+            }
+            Command::Find { path, size, output } => {
+                let actual = match self.resolve_or_current(index, *path) {
+                    Some(node_id) => self.find(&node_id, *size),
+                    None => vec![],
+                };
+                self.check_output(index, output, actual)
+            }
+        }
+    }
+
+    fn root(&self) -> NodeId {
+        self.tree.root_node_id().unwrap().clone()
+    }
+
+    fn name(&self, node_id: &NodeId) -> &'a str {
+        self.tree.get(node_id).unwrap().data().name()
+    }
+
+    fn is_dir(&self, node_id: &NodeId) -> bool {
+        matches!(self.tree.get(node_id).unwrap().data(), Inode::Dir(_))
+    }
+
+    fn child(&self, node_id: &NodeId, name: &str) -> Option<NodeId> {
+        self.tree
+            .children_ids(node_id)
+            .unwrap()
+            .find(|child_id| self.name(child_id) == name)
+            .cloned()
+    }
+
+    /// Get the absolute path of a node
+    pub fn path(&self, node_id: &NodeId) -> String {
+        node_path(&self.tree, node_id)
+    }
+
+    /// Find the node at the given absolute or relative path
+    pub fn resolve(&self, path: &str) -> Option<NodeId> {
+        let mut node_id = if path.starts_with('/') {
+            self.root()
+        } else {
+            self.current.clone()
+        };
+
+        for component in path.split('/') {
+            match component {
+                "" | "." => {}
+                ".." => {
+                    if let Some(parent) = self.tree.get(&node_id).unwrap().parent() {
+                        node_id = parent.clone();
+                    }
+                }
+                name => {
+                    if !self.is_dir(&node_id) {
+                        return None;
+                    }
+                    node_id = self.child(&node_id, name)?;
+                }
+            }
+        }
+
+        Some(node_id)
+    }
+
+    fn resolve_or_current(&mut self, index: usize, path: Option<&str>) -> Option<NodeId> {
+        match path {
+            None => Some(self.current.clone()),
+            Some(path) => {
+                let node_id = self.resolve(path);
+                if node_id.is_none() {
+                    self.inconsistencies.push(Inconsistency::UnknownPath {
+                        command: index,
+                        path: path.to_string(),
+                    });
+                }
+                node_id
+            }
+        }
+    }
+
+    /// Split a path into the directory containing it and its name
+    fn resolve_parent(&self, path: &'a str) -> Option<(NodeId, &'a str)> {
+        let path = path.trim_end_matches('/');
+        let (parent, name) = match path.rsplit_once('/') {
+            Some(("", name)) => (self.root(), name),
+            Some((parent, name)) => (self.resolve(parent)?, name),
+            None => (self.current.clone(), path),
+        };
+
+        match name {
+            "" | "." | ".." => None,
+            _ if self.is_dir(&parent) => Some((parent, name)),
+            _ => None,
+        }
+    }
+
+    /// Add an entry listed by `ls` to the current directory, unless it is already known
+    fn observe(&mut self, index: usize, inode: &Inode<'a>) {
+        let name = inode.name();
+
+        let Some(child_id) = self.child(&self.current, name) else {
+            self.tree
+                .insert(Node::new(inode.clone()), UnderNode(&self.current))
+                .unwrap();
+            return;
+        };
+
+        let path = self.path(&child_id);
+        let known = self.tree.get_mut(&child_id).unwrap().data_mut();
+        match (known, inode) {
+            (Inode::Dir(_), Inode::Dir(_)) => {}
+            (Inode::File(known), Inode::File(file_entry)) => {
+                if known.size != file_entry.size {
+                    self.inconsistencies.push(Inconsistency::ConflictingSize {
+                        command: index,
+                        path,
+                        previous: known.size,
+                        new: file_entry.size,
+                    });
+                    known.size = file_entry.size;
+                }
+            }
+            _ => self.inconsistencies.push(Inconsistency::ConflictingType {
+                command: index,
+                path,
+            }),
+        }
+    }
+
+    fn mkdir(&mut self, index: usize, path: &'a str) {
+        let Some((parent, name)) = self.resolve_parent(path) else {
+            self.inconsistencies.push(Inconsistency::UnknownPath {
+                command: index,
+                path: path.to_string(),
+            });
+            return;
+        };
+
+        if self.child(&parent, name).is_some() {
+            self.inconsistencies.push(Inconsistency::AlreadyExists {
+                command: index,
+                path: path.to_string(),
+            });
+            return;
+        }
+
+        self.tree
+            .insert(
+                Node::new(Inode::Dir(DirEntry { name, size: 0 })),
+                UnderNode(&parent),
+            )
+            .unwrap();
+    }
+
+    fn rm(&mut self, index: usize, path: &str, recursive: bool) {
+        let node_id = match self.resolve(path) {
+            Some(node_id) if node_id != self.root() => node_id,
+            _ => {
+                self.inconsistencies.push(Inconsistency::UnknownPath {
+                    command: index,
+                    path: path.to_string(),
+                });
+                return;
+            }
+        };
+
+        if self.is_dir(&node_id) && !recursive {
+            self.inconsistencies.push(Inconsistency::IsADirectory {
+                command: index,
+                path: path.to_string(),
+            });
+            return;
+        }
+
+        // Removing an ancestor of the current directory moves back to the root
+        if self
+            .tree
+            .ancestor_ids(&self.current)
+            .unwrap()
+            .any(|ancestor_id| *ancestor_id == node_id)
+            || self.current == node_id
+        {
+            self.current = self.root();
+        }
+
+        self.tree.remove_node(node_id, DropChildren).unwrap();
+    }
+
+    fn mv(&mut self, index: usize, src: &str, dst: &'a str) {
+        let src_id = match self.resolve(src) {
+            Some(node_id) if node_id != self.root() => node_id,
+            _ => {
+                self.inconsistencies.push(Inconsistency::UnknownPath {
+                    command: index,
+                    path: src.to_string(),
+                });
+                return;
+            }
+        };
+
+        // Moving into an existing directory keeps the name, otherwise the last component of the
+        // destination is the new name
+        let (parent, name) = match self.resolve(dst) {
+            Some(dst_id) if self.is_dir(&dst_id) => (dst_id, self.name(&src_id)),
+            Some(_) => {
+                self.inconsistencies.push(Inconsistency::AlreadyExists {
+                    command: index,
+                    path: dst.to_string(),
+                });
+                return;
+            }
+            None => match self.resolve_parent(dst) {
+                Some(parent_and_name) => parent_and_name,
+                None => {
+                    self.inconsistencies.push(Inconsistency::UnknownPath {
+                        command: index,
+                        path: dst.to_string(),
+                    });
+                    return;
+                }
+            },
+        };
+
+        let moved_into_itself = parent == src_id
+            || self
+                .tree
+                .ancestor_ids(&parent)
+                .unwrap()
+                .any(|ancestor_id| *ancestor_id == src_id);
+        if moved_into_itself || self.child(&parent, name).is_some() {
+            self.inconsistencies.push(Inconsistency::AlreadyExists {
+                command: index,
+                path: dst.to_string(),
+            });
+            return;
+        }
+
+        match self.tree.get_mut(&src_id).unwrap().data_mut() {
+            Inode::Dir(dir_entry) => dir_entry.name = name,
+            Inode::File(file_entry) => file_entry.name = name,
+        }
+        self.tree.move_node(&src_id, ToParent(&parent)).unwrap();
+    }
+
+    /// Size of a node, computed from the files it contains
+    pub fn size(&self, node_id: &NodeId) -> i64 {
+        match self.tree.get(node_id).unwrap().data() {
+            Inode::File(file_entry) => file_entry.size,
+            Inode::Dir(_) => self
+                .tree
+                .children_ids(node_id)
+                .unwrap()
+                .map(|child_id| self.size(child_id))
+                .sum(),
+        }
+    }
+
+    /// List every directory with its size, children first, like `du` does
+    fn du(&self, node_id: &NodeId, lines: &mut Vec<String>) -> i64 {
+        let mut size = 0;
+        for child_id in self.tree.children_ids(node_id).unwrap() {
+            size += match self.tree.get(child_id).unwrap().data() {
+                Inode::Dir(_) => self.du(child_id, lines),
+                Inode::File(file_entry) => file_entry.size,
+            };
+        }
+        lines.push(format!("{} {}", size, self.path(node_id)));
+
+        size
+    }
+
+    fn find(&self, node_id: &NodeId, size: Option<SizeFilter>) -> Vec<String> {
+        let mut lines = vec![];
+        self.find_from(node_id, &self.path(node_id), size, &mut lines);
+
+        lines
+    }
+
+    /// List the nodes in pre-order like `find` does. The paths are built from the one of the
+    /// parent and the file sizes read from the entries, so that every node is visited once.
+    fn find_from(
+        &self,
+        node_id: &NodeId,
+        path: &str,
+        size: Option<SizeFilter>,
+        lines: &mut Vec<String>,
+    ) {
+        let matches = match (size, self.tree.get(node_id).unwrap().data()) {
+            (None, _) => true,
+            (Some(_), Inode::Dir(_)) => false,
+            (Some(size), Inode::File(file_entry)) => size.matches(file_entry.size),
+        };
+        if matches {
+            lines.push(path.to_string());
+        }
+
+        for child_id in self.tree.children_ids(node_id).unwrap() {
+            let name = self.tree.get(child_id).unwrap().data().name();
+            let child_path = format!("{}/{}", path.trim_end_matches('/'), name);
+            self.find_from(child_id, &child_path, size, lines);
+        }
+    }
+
+    /// Compare the output of the transcript, if any, with the emulated one
+    fn check_output(&mut self, index: usize, output: &[&str], actual: Vec<String>) -> Vec<String> {
+        let expected: Vec<String> = output
+            .iter()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+            .collect();
+
+        if !expected.is_empty() && expected != actual {
+            self.inconsistencies.push(Inconsistency::OutputMismatch {
+                command: index,
+                expected,
+                actual: actual.clone(),
+            });
+        }
+
+        actual
+    }
+}
@@ -1,3 +1,8 @@
+pub mod browser;
+pub mod cleanup;
+pub mod disk;
+pub mod emulator;
 pub mod model;
+pub mod owned;
 pub mod parser;
 pub mod tree;
@@ -1,13 +1,80 @@
-use std::{env, fs};
+use std::fs;
+use std::path::PathBuf;
 
+use clap::{Parser, ValueEnum};
 use id_tree::Tree;
 
+use day07::browser::{browse, Browser};
+use day07::cleanup::CleanupPlanner;
+use day07::disk::{Import, ImportOptions};
+use day07::emulator::FileSystem;
 use day07::model::Inode;
+use day07::owned::{Entry, FormatOptions};
 use day07::parser::parse_data;
-use day07::tree::{generate_tree_from_commands, pretty_print_tree};
+use day07::tree::{export_transcript, pretty_print_tree};
 
-static DISK_SPACE: i64 = 70_000_000;
-static REQUIRED_SPACE: i64 = 30_000_000;
+#[derive(ValueEnum, Debug, Eq, PartialEq, Clone, Copy)]
+enum Format {
+    /// Indented list
+    Pretty,
+    /// Nested JSON objects
+    Json,
+    /// Every entry with its size, biggest first
+    Du,
+    /// Tree with box-drawing characters
+    Tree,
+}
+
+#[derive(Parser, Debug, Eq, PartialEq, Clone)]
+struct Cli {
+    /// Output format of the tree
+    #[arg(short, long, value_enum, default_value_t = Format::Pretty)]
+    format: Format,
+
+    /// Display sizes with a unit suffix
+    #[arg(long, default_value_t = false)]
+    human: bool,
+
+    /// Only display entries up to this depth
+    #[arg(long)]
+    max_depth: Option<usize>,
+
+    /// Import hidden files when reading a directory from the disk
+    #[arg(long, default_value_t = false)]
+    hidden: bool,
+
+    /// Follow symbolic links when reading a directory from the disk
+    #[arg(long, default_value_t = false)]
+    follow_symlinks: bool,
+
+    /// Skip unreadable directories when reading a directory from the disk
+    #[arg(long, default_value_t = false)]
+    skip_unreadable: bool,
+
+    /// Size of the disk
+    #[arg(long, default_value_t = 70_000_000)]
+    disk_size: i64,
+
+    /// Free space required for the update
+    #[arg(long, default_value_t = 30_000_000)]
+    required_space: i64,
+
+    /// Number of cleanup plans to list
+    #[arg(short, long, default_value_t = 0)]
+    top: usize,
+
+    /// Browse the tree interactively
+    #[arg(short, long, default_value_t = false)]
+    browse: bool,
+
+    /// Print the tree as a terminal session instead of analyzing it
+    #[arg(short, long, default_value_t = false)]
+    export: bool,
+
+    /// Terminal session to parse, or directory to read from the disk
+    #[arg(default_value = "input")]
+    path: PathBuf,
+}
 
 fn solve_part_one(data: &Tree<Inode>) {
     let res: i64 = data
@@ -23,12 +90,12 @@ fn solve_part_one(data: &Tree<Inode>) {
     println!("Part one solution: {:#?}", res);
 }
 
-fn solve_part_two(data: &Tree<Inode>) {
+fn solve_part_two(data: &Tree<Inode>, disk_size: i64, required_space: i64) {
     let occupied_space = match data.get(data.root_node_id().unwrap()).unwrap().data() {
         Inode::Dir(dir_entry) => dir_entry.size,
         _ => unreachable!(),
     };
-    let to_delete = REQUIRED_SPACE - DISK_SPACE + occupied_space;
+    let to_delete = required_space - disk_size + occupied_space;
 
     let res = data
         .traverse_pre_order(data.root_node_id().unwrap())
@@ -38,28 +105,88 @@ fn solve_part_two(data: &Tree<Inode>) {
             Inode::File(_) => None,
         })
         .filter(|size| *size >= to_delete)
-        .min()
-        .unwrap();
+        .min();
 
-    println!("Part two solution: {:#?}", res);
+    match res {
+        Some(res) => println!("Part two solution: {:#?}", res),
+        None => println!(
+            "Part two cannot be solved: no directory frees {} bytes",
+            to_delete
+        ),
+    }
 }
 
-fn main() {
-    let args: Vec<_> = env::args().collect();
+fn analyze(args: &Cli, tree: &Tree<Inode>) {
+    if args.browse {
+        let mut browser = Browser::new(tree, args.disk_size, args.required_space);
+        browse(&mut browser).unwrap();
+        return;
+    }
 
-    let file_path = if args.len() > 1 {
-        args[1].clone()
-    } else {
-        "input".to_string()
+    if args.export {
+        print!("{}", export_transcript(tree));
+        return;
+    }
+
+    let options = FormatOptions {
+        human_readable: args.human,
+        max_depth: args.max_depth,
     };
+    match args.format {
+        Format::Pretty => pretty_print_tree(tree),
+        Format::Json => println!("{}", Entry::from_tree(tree).to_json(&options)),
+        Format::Du => print!("{}", Entry::from_tree(tree).to_du(&options)),
+        Format::Tree => print!("{}", Entry::from_tree(tree).to_tree_view(&options)),
+    }
 
-    let data: String = fs::read_to_string(&file_path).unwrap();
+    solve_part_one(tree);
+    solve_part_two(tree, args.disk_size, args.required_space);
 
-    let commands = parse_data(&data);
-    let tree = generate_tree_from_commands(&commands);
+    if args.top > 0 {
+        let planner = CleanupPlanner::new(tree, args.disk_size, args.required_space);
+        println!("Cleanup plans freeing {} bytes:", planner.to_delete());
+        let (plans, error) = planner.candidates(args.top);
+        if let Some(error) = error {
+            println!("No optimal plan: {}", error);
+        }
+        for plan in plans {
+            println!(
+                "- delete {} bytes, {} bytes free: {}",
+                plan.cost,
+                plan.free_space,
+                plan.paths.join(" ")
+            );
+        }
+    }
+}
 
-    pretty_print_tree(&tree);
+fn main() {
+    let args = Cli::parse();
+
+    if args.path.is_dir() {
+        let options = ImportOptions {
+            follow_symlinks: args.follow_symlinks,
+            include_hidden: args.hidden,
+            skip_unreadable: args.skip_unreadable,
+        };
+        let import = Import::from_path(&args.path, &options).unwrap();
+        for (path, error) in &import.errors {
+            eprintln!("Unable to read {}: {}", path.display(), error);
+        }
+
+        analyze(&args, &import.tree());
+        return;
+    }
+
+    let data: String = fs::read_to_string(&args.path).unwrap();
+
+    let commands = parse_data(&data);
+    let mut file_system = FileSystem::new();
+    file_system.replay(&commands);
+    for inconsistency in file_system.inconsistencies() {
+        println!("Inconsistency: {:?}", inconsistency);
+    }
+    let tree = file_system.into_tree();
 
-    solve_part_one(&tree);
-    solve_part_two(&tree);
+    analyze(&args, &tree);
 }
@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct DirEntry<'a> {
     pub name: &'a str,
@@ -16,6 +18,22 @@ pub enum Inode<'a> {
     File(FileEntry<'a>),
 }
 
+impl<'a> Inode<'a> {
+    pub fn name(&self) -> &'a str {
+        match self {
+            Inode::Dir(dir_entry) => dir_entry.name,
+            Inode::File(file_entry) => file_entry.name,
+        }
+    }
+
+    pub fn size(&self) -> i64 {
+        match self {
+            Inode::Dir(dir_entry) => dir_entry.size,
+            Inode::File(file_entry) => file_entry.size,
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum CdTarget<'a> {
     Root,
@@ -23,8 +41,45 @@ pub enum CdTarget<'a> {
     Directory(&'a str),
 }
 
+/// Size test of `find`, the size of the file is rounded up to a whole number of units before
+/// being compared with the value, 512-byte blocks unless a suffix is given
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct SizeFilter {
+    pub ordering: Ordering,
+    pub value: i64,
+    pub unit: i64,
+}
+
+impl SizeFilter {
+    pub fn matches(&self, size: i64) -> bool {
+        let units = (size + self.unit - 1) / self.unit;
+        units.cmp(&self.value) == self.ordering
+    }
+}
+
+/// A command of a terminal session. Commands producing an output keep the output lines found in
+/// the transcript, if any, so that they can be checked.
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum Command<'a> {
     Ls(Vec<Inode<'a>>),
     Cd(CdTarget<'a>),
+    Pwd(Vec<&'a str>),
+    Mkdir(&'a str),
+    Rm {
+        path: &'a str,
+        recursive: bool,
+    },
+    Mv {
+        src: &'a str,
+        dst: &'a str,
+    },
+    Du {
+        path: Option<&'a str>,
+        output: Vec<&'a str>,
+    },
+    Find {
+        path: Option<&'a str>,
+        size: Option<SizeFilter>,
+        output: Vec<&'a str>,
+    },
 }
@@ -0,0 +1,211 @@
+use std::fmt::Write;
+
+use id_tree::InsertBehavior::{AsRoot, UnderNode};
+use id_tree::{Node, NodeId, Tree, TreeBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::model::{DirEntry, FileEntry, Inode};
+use crate::tree::node_path;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    Dir,
+    File,
+}
+
+/// Owned version of a tree of inodes, which does not depend on the parsed input and can be
+/// serialized.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub kind: EntryKind,
+    pub name: String,
+    pub path: String,
+    pub size: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Entry>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub struct FormatOptions {
+    /// Display sizes with a unit suffix, like `du -h`
+    pub human_readable: bool,
+    /// Only display entries up to this depth, the root being at depth 0
+    pub max_depth: Option<usize>,
+}
+
+impl Entry {
+    pub fn from_tree(tree: &Tree<Inode>) -> Self {
+        Self::from_node(tree, tree.root_node_id().unwrap())
+    }
+
+    fn from_node(tree: &Tree<Inode>, node_id: &NodeId) -> Self {
+        let inode = tree.get(node_id).unwrap().data();
+        Self {
+            kind: match inode {
+                Inode::Dir(_) => EntryKind::Dir,
+                Inode::File(_) => EntryKind::File,
+            },
+            name: inode.name().to_string(),
+            path: node_path(tree, node_id),
+            size: inode.size(),
+            children: tree
+                .children_ids(node_id)
+                .unwrap()
+                .map(|child_id| Self::from_node(tree, child_id))
+                .collect(),
+        }
+    }
+
+    /// Build a tree of inodes borrowing their names from the entries
+    pub fn to_tree(&self) -> Tree<Inode<'_>> {
+        let mut tree: Tree<Inode> = TreeBuilder::new().build();
+        self.insert_into(&mut tree, None);
+
+        tree
+    }
+
+    fn insert_into<'a>(&'a self, tree: &mut Tree<Inode<'a>>, parent: Option<&NodeId>) {
+        let inode = match self.kind {
+            EntryKind::Dir => Inode::Dir(DirEntry {
+                name: &self.name,
+                size: self.size,
+            }),
+            EntryKind::File => Inode::File(FileEntry {
+                name: &self.name,
+                size: self.size,
+            }),
+        };
+        let behavior = match parent {
+            None => AsRoot,
+            Some(parent) => UnderNode(parent),
+        };
+        let node_id = tree.insert(Node::new(inode), behavior).unwrap();
+
+        for child in &self.children {
+            child.insert_into(tree, Some(&node_id));
+        }
+    }
+
+    /// Copy of the entry without the entries deeper than `max_depth`
+    pub fn truncated(&self, max_depth: usize) -> Self {
+        Self {
+            children: match max_depth {
+                0 => vec![],
+                _ => self
+                    .children
+                    .iter()
+                    .map(|child| child.truncated(max_depth - 1))
+                    .collect(),
+            },
+            ..self.clone()
+        }
+    }
+
+    fn limited(&self, options: &FormatOptions) -> Self {
+        match options.max_depth {
+            None => self.clone(),
+            Some(max_depth) => self.truncated(max_depth),
+        }
+    }
+
+    /// Write the entry as nested JSON objects
+    pub fn to_json(&self, options: &FormatOptions) -> String {
+        serde_json::to_string_pretty(&self.limited(options)).unwrap()
+    }
+
+    /// Write one line per entry with its size and path, biggest first, like `du -a` piped into
+    /// `sort -rn`
+    pub fn to_du(&self, options: &FormatOptions) -> String {
+        let limited = self.limited(options);
+        let mut entries = vec![];
+        limited.flatten(&mut entries);
+        entries.sort_by(|left, right| {
+            right
+                .size
+                .cmp(&left.size)
+                .then_with(|| left.path.cmp(&right.path))
+        });
+
+        entries.iter().fold(String::new(), |mut output, entry| {
+            let _ = writeln!(
+                output,
+                "{}\t{}",
+                format_size(entry.size, options.human_readable),
+                entry.path
+            );
+            output
+        })
+    }
+
+    fn flatten<'e>(&'e self, entries: &mut Vec<&'e Entry>) {
+        entries.push(self);
+        for child in &self.children {
+            child.flatten(entries);
+        }
+    }
+
+    /// Write the entry like the `tree` command does, with box-drawing characters
+    pub fn to_tree_view(&self, options: &FormatOptions) -> String {
+        let limited = self.limited(options);
+        let mut output = format!(
+            "{} ({})\n",
+            limited.name,
+            format_size(limited.size, options.human_readable)
+        );
+        limited.write_tree_view_children(options, "", &mut output);
+
+        output
+    }
+
+    fn write_tree_view_children(&self, options: &FormatOptions, prefix: &str, output: &mut String) {
+        for (index, child) in self.children.iter().enumerate() {
+            let last = index == self.children.len() - 1;
+            let _ = writeln!(
+                output,
+                "{}{} {} ({})",
+                prefix,
+                if last { "└──" } else { "├──" },
+                child.name,
+                format_size(child.size, options.human_readable)
+            );
+
+            let child_prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+            child.write_tree_view_children(options, &child_prefix, output);
+        }
+    }
+}
+
+/// Format a size in bytes, or with a binary unit suffix when `human_readable` is set
+pub fn format_size(size: i64, human_readable: bool) -> String {
+    if !human_readable || size < 1024 {
+        return size.to_string();
+    }
+
+    let mut value = size as f64;
+    let mut unit = 0;
+    let units = ["K", "M", "G", "T", "P"];
+    while value >= 1024.0 && unit < units.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    loop {
+        // Like du, use one decimal for small values
+        let rounded = if value < 10.0 {
+            (value * 10.0).round() / 10.0
+        } else {
+            value.round()
+        };
+
+        // Rounding may reach the next unit
+        if rounded >= 1024.0 && unit < units.len() {
+            value /= 1024.0;
+            unit += 1;
+        } else if rounded < 10.0 {
+            return format!("{:.1}{}", rounded, units[unit - 1]);
+        } else {
+            return format!("{:.0}{}", rounded, units[unit - 1]);
+        }
+    }
+}
@@ -1,32 +1,70 @@
+use std::cmp::Ordering;
 use std::num::ParseIntError;
 use std::str::FromStr;
 
 use nom::branch::alt;
-use nom::character::complete::alphanumeric1;
-use nom::multi::many1;
+use nom::bytes::complete::is_not;
+use nom::character::complete::{alpha1, one_of, space1};
+use nom::combinator::{not, opt, peek};
+use nom::multi::{many0, many1};
 use nom::sequence::preceded;
 use nom::{
-    bytes::complete::{tag, take_while1},
+    bytes::complete::tag,
     character::complete::{digit1, line_ending, multispace1},
     combinator::{map, map_res},
     sequence::{delimited, pair, terminated, tuple},
     IResult, Parser,
 };
 
-use crate::model::{CdTarget, Command, DirEntry, FileEntry, Inode};
+use crate::model::{CdTarget, Command, DirEntry, FileEntry, Inode, SizeFilter};
 
-#[inline]
-fn is_alphanumeric_dot(c: char) -> bool {
-    c.is_alphanumeric() || c == '.'
+/// Parse a file name or a path, which can contain anything but whitespaces
+pub fn parse_name(input: &str) -> IResult<&str, &str> {
+    is_not(" \t\r\n").parse(input)
+}
+
+/// Parse a file name or a path up to the end of the line, so that it may contain spaces
+pub fn parse_line_name(input: &str) -> IResult<&str, &str> {
+    is_not("\r\n").parse(input)
+}
+
+/// Parse a name or a path between single or double quotes, which can contain anything but the
+/// quote and line endings
+pub fn parse_quoted_name(input: &str) -> IResult<&str, &str> {
+    alt((
+        delimited(tag("\""), is_not("\"\r\n"), tag("\"")),
+        delimited(tag("'"), is_not("'\r\n"), tag("'")),
+    ))
+    .parse(input)
+}
+
+/// Parse a command argument, quoted if it contains spaces
+pub fn parse_argument(input: &str) -> IResult<&str, &str> {
+    alt((parse_quoted_name, parse_name)).parse(input)
+}
+
+/// Parse the last argument of a command, which runs up to the end of the line unless it is
+/// quoted
+pub fn parse_last_argument(input: &str) -> IResult<&str, &str> {
+    alt((
+        terminated(parse_quoted_name, peek(line_ending)),
+        parse_line_name,
+    ))
+    .parse(input)
+}
+
+/// Parse the output lines of a command, up to the next command
+pub fn parse_output(input: &str) -> IResult<&str, Vec<&str>> {
+    many0(terminated(
+        preceded(not(tag("$")), is_not("\r\n")),
+        line_ending,
+    ))
+    .parse(input)
 }
 
 pub fn parse_dir_line(input: &str) -> IResult<&str, DirEntry> {
     map(
-        delimited(
-            pair(tag("dir"), multispace1),
-            take_while1(is_alphanumeric_dot),
-            line_ending,
-        ),
+        delimited(pair(tag("dir"), space1), parse_line_name, line_ending),
         |name| DirEntry { name, size: 0 },
     )
     .parse(input)
@@ -34,10 +72,7 @@ pub fn parse_dir_line(input: &str) -> IResult<&str, DirEntry> {
 
 pub fn parse_file_line(input: &str) -> IResult<&str, FileEntry> {
     map_res(
-        terminated(
-            tuple((digit1, multispace1, take_while1(is_alphanumeric_dot))),
-            line_ending,
-        ),
+        terminated(tuple((digit1, space1, parse_line_name)), line_ending),
         |(size, _, name)| {
             Ok::<_, ParseIntError>(FileEntry {
                 name,
@@ -59,28 +94,156 @@ pub fn parse_ls_line(input: &str) -> IResult<&str, Inode> {
 pub fn parse_ls_command(input: &str) -> IResult<&str, Vec<Inode>> {
     preceded(
         tuple((tag("$"), multispace1, tag("ls"), line_ending)),
-        many1(parse_ls_line),
+        many0(parse_ls_line),
     )
     .parse(input)
 }
 
 pub fn parse_cd_command(input: &str) -> IResult<&str, CdTarget> {
     delimited(
-        tuple((tag("$"), multispace1, tag("cd"), multispace1)),
-        alt((
-            map(tag("/"), |_| CdTarget::Root),
-            map(tag(".."), |_| CdTarget::Up),
-            map(alphanumeric1, |res| CdTarget::Directory(res)),
-        )),
+        tuple((tag("$"), multispace1, tag("cd"), space1)),
+        map(parse_last_argument, |res| match res {
+            "/" => CdTarget::Root,
+            ".." => CdTarget::Up,
+            path => CdTarget::Directory(path),
+        }),
         line_ending,
     )
     .parse(input)
 }
 
+pub fn parse_pwd_command(input: &str) -> IResult<&str, Vec<&str>> {
+    preceded(
+        tuple((tag("$"), multispace1, tag("pwd"), line_ending)),
+        parse_output,
+    )
+    .parse(input)
+}
+
+pub fn parse_mkdir_command(input: &str) -> IResult<&str, &str> {
+    delimited(
+        tuple((tag("$"), multispace1, tag("mkdir"), space1)),
+        parse_last_argument,
+        line_ending,
+    )
+    .parse(input)
+}
+
+pub fn parse_rm_command(input: &str) -> IResult<&str, (&str, bool)> {
+    map(
+        delimited(
+            tuple((tag("$"), multispace1, tag("rm"), space1)),
+            pair(
+                opt(terminated(preceded(tag("-"), alpha1), space1)),
+                parse_last_argument,
+            ),
+            line_ending,
+        ),
+        |(flags, path): (Option<&str>, &str)| {
+            let recursive = flags.is_some_and(|flags| flags.contains(['r', 'R']));
+            (path, recursive)
+        },
+    )
+    .parse(input)
+}
+
+pub fn parse_mv_command(input: &str) -> IResult<&str, (&str, &str)> {
+    map(
+        delimited(
+            tuple((tag("$"), multispace1, tag("mv"), space1)),
+            tuple((parse_argument, space1, parse_last_argument)),
+            line_ending,
+        ),
+        |(src, _, dst)| (src, dst),
+    )
+    .parse(input)
+}
+
+pub fn parse_du_command(input: &str) -> IResult<&str, (Option<&str>, Vec<&str>)> {
+    pair(
+        delimited(
+            tuple((tag("$"), multispace1, tag("du"))),
+            opt(preceded(space1, parse_last_argument)),
+            line_ending,
+        ),
+        parse_output,
+    )
+    .parse(input)
+}
+
+pub fn parse_size_filter(input: &str) -> IResult<&str, SizeFilter> {
+    map_res(
+        tuple((
+            opt(alt((tag("+"), tag("-")))),
+            digit1,
+            opt(one_of("cwbkMG")),
+        )),
+        |(sign, value, suffix)| {
+            Ok::<_, ParseIntError>(SizeFilter {
+                ordering: match sign {
+                    Some("+") => Ordering::Greater,
+                    Some(_) => Ordering::Less,
+                    None => Ordering::Equal,
+                },
+                value: i64::from_str(value)?,
+                unit: match suffix {
+                    Some('c') => 1,
+                    Some('w') => 2,
+                    Some('k') => 1 << 10,
+                    Some('M') => 1 << 20,
+                    Some('G') => 1 << 30,
+                    _ => 512,
+                },
+            })
+        },
+    )
+    .parse(input)
+}
+
+/// Path, size filter and output lines of a `find` command
+type FindCommand<'a> = (Option<&'a str>, Option<SizeFilter>, Vec<&'a str>);
+
+pub fn parse_find_command(input: &str) -> IResult<&str, FindCommand<'_>> {
+    map(
+        pair(
+            delimited(
+                tuple((tag("$"), multispace1, tag("find"))),
+                pair(
+                    opt(preceded(space1, preceded(not(tag("-")), parse_argument))),
+                    opt(preceded(
+                        tuple((space1, tag("-size"), space1)),
+                        parse_size_filter,
+                    )),
+                ),
+                line_ending,
+            ),
+            parse_output,
+        ),
+        |((path, size), output)| (path, size, output),
+    )
+    .parse(input)
+}
+
 pub fn parse_input(input: &str) -> IResult<&str, Vec<Command>> {
     many1(alt((
         map(parse_ls_command, |res| Command::Ls(res)),
         map(parse_cd_command, |res| Command::Cd(res)),
+        map(parse_pwd_command, Command::Pwd),
+        map(parse_mkdir_command, Command::Mkdir),
+        map(parse_rm_command, |(path, recursive)| Command::Rm {
+            path,
+            recursive,
+        }),
+        map(parse_mv_command, |(src, dst)| Command::Mv { src, dst }),
+        map(parse_du_command, |(path, output)| Command::Du {
+            path,
+            output,
+        }),
+        map(parse_find_command, |(path, size, output)| Command::Find {
+            path,
+            size,
+            output,
+        }),
     )))
     .parse(input)
 }
@@ -1,48 +1,33 @@
-use crate::model::{CdTarget, Command, DirEntry, Inode};
-use id_tree::InsertBehavior::{AsRoot, UnderNode};
-use id_tree::{Node, NodeId, Tree, TreeBuilder};
-
-pub fn generate_tree_from_commands<'a, 'b>(commands: &'b [Command<'a>]) -> Tree<Inode<'a>> {
-    let mut tree: Tree<Inode> = TreeBuilder::new().build();
-    let root_node = tree
-        .insert(
-            Node::new(Inode::Dir(DirEntry { name: "/", size: 0 })),
-            AsRoot,
-        )
-        .unwrap();
-
-    let mut current_node = root_node.clone();
-    for command in commands {
-        match command {
-            Command::Ls(ls_lines) => {
-                for ls_line in ls_lines {
-                    tree.insert(Node::new(ls_line.clone()), UnderNode(&current_node))
-                        .unwrap();
-                }
-            }
-            Command::Cd(cd_target) => match cd_target {
-                CdTarget::Root => {
-                    current_node = root_node.clone();
-                }
-                CdTarget::Up => {
-                    current_node = tree.get(&current_node).unwrap().parent().unwrap().clone()
-                }
-                CdTarget::Directory(dir_name) => {
-                    for children in tree.children_ids(&current_node).unwrap() {
-                        let node = tree.get(children).unwrap();
-                        if let &Inode::Dir(DirEntry { name, size: 0 }) = node.data() {
-                            if name == *dir_name {
-                                current_node = children.clone();
-                                break;
-                            }
-                        }
-                    }
-                }
-            },
-        }
-    }
+use crate::emulator::FileSystem;
+use crate::model::{Command, DirEntry, Inode};
+use id_tree::{NodeId, Tree};
 
-    compute_tree_dir_size(tree)
+/// Replay the commands on an emulated filesystem, inconsistencies of the transcript are ignored
+pub fn generate_tree_from_commands<'a>(commands: &[Command<'a>]) -> Tree<Inode<'a>> {
+    let mut file_system = FileSystem::new();
+    file_system.replay(commands);
+
+    file_system.into_tree()
+}
+
+/// Get the absolute path of a node
+pub fn node_path(tree: &Tree<Inode>, node_id: &NodeId) -> String {
+    let mut names: Vec<&str> = tree
+        .ancestor_ids(node_id)
+        .unwrap()
+        .map(|ancestor_id| tree.get(ancestor_id).unwrap().data().name())
+        .collect();
+    names.reverse();
+    names.push(tree.get(node_id).unwrap().data().name());
+
+    match names.len() {
+        1 => "/".to_string(),
+        _ => names[1..].iter().fold(String::new(), |mut path, name| {
+            path.push('/');
+            path.push_str(name);
+            path
+        }),
+    }
 }
 
 pub fn pretty_print_tree(tree: &Tree<Inode>) {
@@ -74,7 +59,7 @@ fn pretty_print_tree_inner(tree: &Tree<Inode>, current_node: &NodeId, mut offset
     }
 }
 
-fn compute_tree_dir_size(mut tree: Tree<Inode>) -> Tree<Inode> {
+pub fn compute_tree_dir_size(mut tree: Tree<Inode>) -> Tree<Inode> {
     let root = tree.root_node_id().unwrap().clone();
     compute_tree_dir_size_inner(&mut tree, &root);
 
@@ -101,3 +86,31 @@ fn compute_tree_dir_size_inner(tree: &mut Tree<Inode>, current_node: &NodeId) ->
         Inode::File(file_entry) => file_entry.size,
     }
 }
+
+/// Write a tree as a terminal session listing every directory, which can be parsed back
+pub fn export_transcript(tree: &Tree<Inode>) -> String {
+    let mut transcript = String::from("$ cd /\n");
+    export_transcript_inner(tree, tree.root_node_id().unwrap(), &mut transcript);
+
+    transcript
+}
+
+fn export_transcript_inner(tree: &Tree<Inode>, current_node: &NodeId, transcript: &mut String) {
+    transcript.push_str("$ ls\n");
+    for children_id in tree.children_ids(current_node).unwrap() {
+        match tree.get(children_id).unwrap().data() {
+            Inode::Dir(dir_entry) => transcript.push_str(&format!("dir {}\n", dir_entry.name)),
+            Inode::File(file_entry) => {
+                transcript.push_str(&format!("{} {}\n", file_entry.size, file_entry.name))
+            }
+        }
+    }
+
+    for children_id in tree.children_ids(current_node).unwrap() {
+        if let Inode::Dir(dir_entry) = tree.get(children_id).unwrap().data() {
+            transcript.push_str(&format!("$ cd {}\n", dir_entry.name));
+            export_transcript_inner(tree, children_id, transcript);
+            transcript.push_str("$ cd ..\n");
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use day07::browser::{Action, Browser, SortBy};
+use day07::parser::parse_data;
+use day07::tree::generate_tree_from_commands;
+
+static TRANSCRIPT: &str = "$ cd /
+$ ls
+dir a
+30 b
+10 c
+$ cd a
+$ ls
+20 x
+40 y
+";
+
+#[test]
+fn test_navigation() {
+    let commands = parse_data(TRANSCRIPT);
+    let tree = generate_tree_from_commands(&commands);
+    let mut browser = Browser::new(&tree, 200, 150);
+
+    let names = |browser: &Browser| -> Vec<String> {
+        browser
+            .entries()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect()
+    };
+
+    assert_eq!(names(&browser), vec!["a", "b", "c"]);
+    assert_eq!(browser.entries()[0].percent, 60.0);
+
+    browser.apply(Action::Sort(SortBy::Name));
+    browser.apply(Action::Down);
+    assert_eq!(browser.selected(), 1);
+    browser.apply(Action::Sort(SortBy::Size));
+    // The selection follows the entry
+    assert_eq!(browser.selected(), 1);
+    browser.apply(Action::Up);
+
+    browser.apply(Action::Enter);
+    assert_eq!(browser.current_path(), "/a");
+    assert_eq!(names(&browser), vec!["y", "x"]);
+
+    browser.apply(Action::Leave);
+    assert_eq!(browser.current_path(), "/");
+    assert_eq!(browser.selected(), 0);
+
+    assert!(!browser.apply(Action::Quit));
+}
+
+#[test]
+fn test_marks() {
+    let commands = parse_data(TRANSCRIPT);
+    let tree = generate_tree_from_commands(&commands);
+    // 100 bytes used, 100 bytes free
+    let mut browser = Browser::new(&tree, 200, 150);
+
+    assert_eq!(browser.free_space(), 100);
+    assert!(!browser.has_enough_space());
+
+    browser.apply(Action::Enter);
+    browser.apply(Action::ToggleMark);
+    assert_eq!(browser.marked_size(), 40);
+
+    // Marking the directory replaces the marks of its content
+    browser.apply(Action::Leave);
+    browser.apply(Action::ToggleMark);
+    assert_eq!(browser.marked_size(), 60);
+    assert!(browser.has_enough_space());
+    browser.apply(Action::Enter);
+    assert!(browser.entries().iter().all(|entry| entry.marked));
+
+    // Unmarking an entry of the marked directory keeps the rest of it marked
+    browser.apply(Action::ToggleMark);
+    assert_eq!(browser.marked_size(), 20);
+    assert!(!browser.entries()[0].marked);
+    assert!(browser.entries()[1].marked);
+    browser.apply(Action::ToggleMark);
+    assert_eq!(browser.marked_size(), 60);
+
+    // The directory is marked again, then unmarked with its content
+    browser.apply(Action::Leave);
+    browser.apply(Action::ToggleMark);
+    assert_eq!(browser.marked_size(), 60);
+    browser.apply(Action::ToggleMark);
+    assert_eq!(browser.marked_size(), 0);
+}
+
+#[test]
+fn test_render() {
+    let commands = parse_data(TRANSCRIPT);
+    let tree = generate_tree_from_commands(&commands);
+    let mut browser = Browser::new(&tree, 200, 150);
+    browser.apply(Action::Down);
+    browser.apply(Action::ToggleMark);
+
+    assert_eq!(
+        browser.render(80, 10),
+        vec![
+            "--- / --- sorted by size",
+            "Free space: 130 / 150 required, 30 marked for deletion: not enough",
+            "        60  60.0% [######    ] a/",
+            ">*      30  30.0% [###       ] b",
+            "        10  10.0% [#         ] c",
+            "up/down: move  enter/right: open  left: back  s/n: sort  space: mark  q: quit",
+        ]
+    );
+    assert_eq!(browser.render(80, 4).len(), 4);
+}
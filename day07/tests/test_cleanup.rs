@@ -0,0 +1,145 @@
+use day07::cleanup::{CleanupError, CleanupPlanner, Plan};
+use day07::parser::parse_data;
+use day07::tree::generate_tree_from_commands;
+
+static TRANSCRIPT: &str = "$ cd /
+$ ls
+dir a
+30 b
+12 c
+$ cd a
+$ ls
+20 x
+15 y
+dir z
+$ cd z
+$ ls
+";
+
+#[test]
+fn test_optimal_plan() {
+    let commands = parse_data(TRANSCRIPT);
+    let tree = generate_tree_from_commands(&commands);
+
+    // 77 bytes are used, 27 must be deleted
+    let planner = CleanupPlanner::new(&tree, 100, 50);
+    assert_eq!(planner.to_delete(), 27);
+    assert_eq!(
+        planner.optimal_plan(),
+        Ok(Plan {
+            paths: vec!["/a/y".to_string(), "/c".to_string()],
+            cost: 27,
+            free_space: 50,
+        })
+    );
+
+    // Deleting every file of a directory deletes the directory
+    let planner = CleanupPlanner::new(&tree, 100, 58);
+    assert_eq!(planner.optimal_plan().unwrap().paths, vec!["/a"]);
+
+    let planner = CleanupPlanner::new(&tree, 100, 200);
+    assert_eq!(
+        planner.optimal_plan(),
+        Err(CleanupError::NotEnoughFiles {
+            to_delete: 177,
+            total: 77
+        })
+    );
+
+    let planner = CleanupPlanner::new(&tree, 100, 10);
+    assert_eq!(planner.optimal_plan().unwrap().cost, 0);
+}
+
+#[test]
+fn test_candidates() {
+    let commands = parse_data(TRANSCRIPT);
+    let tree = generate_tree_from_commands(&commands);
+    let planner = CleanupPlanner::new(&tree, 100, 50);
+
+    assert_eq!(
+        planner
+            .candidates(3)
+            .0
+            .into_iter()
+            .map(|plan| (plan.paths, plan.cost, plan.free_space))
+            .collect::<Vec<_>>(),
+        vec![
+            (vec!["/a/y".to_string(), "/c".to_string()], 27, 50),
+            (vec!["/b".to_string()], 30, 53),
+            (vec!["/a".to_string()], 35, 58),
+        ]
+    );
+}
+
+/// Transcript of a disk holding `count` files of about `size` bytes in two directories
+fn big_transcript(count: usize, size: i64) -> String {
+    let mut transcript = String::from("$ cd /\n$ ls\ndir a\ndir b\n");
+    for directory in ["a", "b"] {
+        transcript.push_str(&format!("$ cd {}\n$ ls\n", directory));
+        for index in 0..count / 2 {
+            // Distinct sizes without any common divisor
+            transcript.push_str(&format!("{} {}\n", size + index as i64 * 7919 + 1, index));
+        }
+        transcript.push_str("$ cd ..\n");
+    }
+
+    transcript
+}
+
+#[test]
+fn test_optimal_plan_large_sizes() {
+    // 50 files of about 1 GB: a table indexed by byte would need gigabytes
+    let transcript = big_transcript(50, 1_000_000_000);
+    let commands = parse_data(&transcript);
+    let tree = generate_tree_from_commands(&commands);
+    let size = |index: i64| 1_000_000_000 + index * 7919 + 1;
+    let used: i64 = 2 * (0..25).map(size).sum::<i64>();
+
+    // Exactly the size of 5 files must be deleted
+    let to_delete = size(3) + size(7) + size(12) + size(20) + size(5);
+    let disk_size = 70_000_000_000;
+    let planner = CleanupPlanner::new(&tree, disk_size, disk_size - used + to_delete);
+    assert_eq!(planner.to_delete(), to_delete);
+
+    let plan = planner.optimal_plan().unwrap();
+    assert_eq!(plan.cost, to_delete);
+    assert_eq!(plan.paths.len(), 5);
+}
+
+#[test]
+fn test_optimal_plan_block_sizes() {
+    // Sizes multiple of 4096, the table counts blocks instead of bytes
+    let transcript = "$ cd /
+$ ls
+40960000000 a
+20480000000 b
+12288000000 c
+8192000000 d
+";
+    let commands = parse_data(transcript);
+    let tree = generate_tree_from_commands(&commands);
+
+    // 81920000000 bytes are used, 20480000001 must be deleted: /c and /d are one byte short
+    let planner = CleanupPlanner::new(&tree, 100_000_000_000, 38_560_000_001);
+    assert_eq!(
+        planner.optimal_plan().unwrap().paths,
+        vec!["/b".to_string(), "/d".to_string()]
+    );
+}
+
+#[test]
+fn test_optimal_plan_search_limit() {
+    let transcript = big_transcript(60, 1_000_000_000);
+    let commands = parse_data(&transcript);
+    let tree = generate_tree_from_commands(&commands);
+
+    let planner = CleanupPlanner::new(&tree, 70_000_000_000, 30_000_000_000).with_search_limit(10);
+    assert_eq!(
+        planner.optimal_plan(),
+        Err(CleanupError::SearchLimit { limit: 10 })
+    );
+    // The single entries are still listed along with the error
+    let (plans, error) = planner.candidates(3);
+    assert!(!plans.is_empty());
+    assert_eq!(error, Some(CleanupError::SearchLimit { limit: 10 }));
+}
@@ -0,0 +1,144 @@
+use std::fs;
+use std::path::PathBuf;
+
+use day07::disk::{Import, ImportOptions};
+use day07::model::Inode;
+use day07::parser::parse_data;
+use day07::tree::{export_transcript, generate_tree_from_commands};
+
+/// Create a fresh directory holding a small tree:
+/// a/b (10 bytes), a/.c (20 bytes), .d/e (30 bytes), f (40 bytes)
+fn create_tree(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("day07_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(root.join("a")).unwrap();
+    fs::create_dir_all(root.join(".d")).unwrap();
+    fs::write(root.join("a/b"), [0; 10]).unwrap();
+    fs::write(root.join("a/.c"), [0; 20]).unwrap();
+    fs::write(root.join(".d/e"), [0; 30]).unwrap();
+    fs::write(root.join("f"), [0; 40]).unwrap();
+
+    root
+}
+
+fn root_size(import: &Import) -> i64 {
+    let tree = import.tree();
+    match tree.get(tree.root_node_id().unwrap()).unwrap().data() {
+        Inode::Dir(dir_entry) => dir_entry.size,
+        Inode::File(_) => unreachable!(),
+    }
+}
+
+#[test]
+fn test_import_hidden() {
+    let root = create_tree("hidden");
+
+    let import = Import::from_path(&root, &ImportOptions::default()).unwrap();
+    assert_eq!(root_size(&import), 50);
+
+    let options = ImportOptions {
+        include_hidden: true,
+        ..ImportOptions::default()
+    };
+    let import = Import::from_path(&root, &options).unwrap();
+    assert_eq!(root_size(&import), 100);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_import_symlinks() {
+    let root = create_tree("symlinks");
+    std::os::unix::fs::symlink(root.join("a"), root.join("g")).unwrap();
+    // A link to an ancestor must not be followed forever
+    std::os::unix::fs::symlink(&root, root.join("a/h")).unwrap();
+
+    let import = Import::from_path(&root, &ImportOptions::default()).unwrap();
+    assert_eq!(root_size(&import), 50);
+
+    let options = ImportOptions {
+        follow_symlinks: true,
+        ..ImportOptions::default()
+    };
+    let import = Import::from_path(&root, &options).unwrap();
+    assert_eq!(root_size(&import), 60);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_export_round_trip() {
+    let root = create_tree("export");
+
+    let import = Import::from_path(&root, &ImportOptions::default()).unwrap();
+    let tree = import.tree();
+    let transcript = export_transcript(&tree);
+
+    assert_eq!(
+        transcript,
+        "$ cd /
+$ ls
+dir a
+40 f
+$ cd a
+$ ls
+10 b
+$ cd ..
+"
+    );
+
+    let commands = parse_data(&transcript);
+    let parsed_tree = generate_tree_from_commands(&commands);
+    assert_eq!(export_transcript(&parsed_tree), transcript);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_export_names_with_spaces() {
+    let root = std::env::temp_dir().join(format!("day07_spaces_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("My Documents")).unwrap();
+    fs::write(root.join("My Documents/tax return.pdf"), [0; 10]).unwrap();
+    fs::write(root.join("notes"), [0; 5]).unwrap();
+
+    let import = Import::from_path(&root, &ImportOptions::default()).unwrap();
+    let transcript = export_transcript(&import.tree());
+    assert_eq!(
+        transcript,
+        "$ cd /
+$ ls
+dir My Documents
+5 notes
+$ cd My Documents
+$ ls
+10 tax return.pdf
+$ cd ..
+"
+    );
+
+    let commands = parse_data(&transcript);
+    let parsed_tree = generate_tree_from_commands(&commands);
+    assert_eq!(export_transcript(&parsed_tree), transcript);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_import_skip_unreadable() {
+    let root = std::env::temp_dir().join(format!("day07_missing_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+
+    assert!(Import::from_path(&root, &ImportOptions::default()).is_err());
+
+    let options = ImportOptions {
+        skip_unreadable: true,
+        ..ImportOptions::default()
+    };
+    let import = Import::from_path(&root, &options).unwrap();
+    assert_eq!(root_size(&import), 0);
+    assert_eq!(import.errors.len(), 1);
+    assert_eq!(import.errors[0].0, root);
+}
@@ -0,0 +1,147 @@
+use day07::emulator::{FileSystem, Inconsistency};
+use day07::model::Inode;
+use day07::parser::parse_data;
+
+fn replay(transcript: &str) -> FileSystem<'_> {
+    let mut file_system = FileSystem::new();
+    file_system.replay(&parse_data(transcript));
+    file_system
+}
+
+fn size(file_system: &FileSystem, path: &str) -> i64 {
+    file_system.size(&file_system.resolve(path).unwrap())
+}
+
+#[test]
+fn test_repeated_ls() {
+    let file_system = replay(
+        "$ cd /
+$ ls
+dir a
+10 b
+$ ls
+dir a
+10 b
+$ cd a
+$ ls
+20 c
+$ cd /
+$ ls
+dir a
+10 b
+",
+    );
+
+    assert!(file_system.inconsistencies().is_empty());
+    assert_eq!(size(&file_system, "/"), 30);
+
+    let tree = file_system.into_tree();
+    let root = tree.root_node_id().unwrap();
+    assert_eq!(tree.children_ids(root).unwrap().count(), 2);
+    assert!(matches!(tree.get(root).unwrap().data(), Inode::Dir(dir) if dir.size == 30));
+}
+
+#[test]
+fn test_file_operations() {
+    let file_system = replay(
+        "$ cd /
+$ mkdir a.b
+$ cd a.b
+$ ls
+10 c
+$ mkdir d
+$ mv c d/e
+$ pwd
+/a.b
+$ cd d
+$ pwd
+/a.b/d
+$ cd /
+$ mv a.b/d .
+$ rm -r a.b
+$ du
+10 /d
+10 /
+$ find d -size -11
+/d/e
+$ find -size 1
+/d/e
+$ find / -size 10c
+/d/e
+",
+    );
+
+    assert!(file_system.inconsistencies().is_empty());
+    assert_eq!(file_system.resolve("a.b"), None);
+    assert_eq!(size(&file_system, "/d/e"), 10);
+}
+
+#[test]
+fn test_names_with_spaces() {
+    let file_system = replay(
+        "$ cd /
+$ mkdir My Documents
+$ cd My Documents
+$ ls
+10 tax return.pdf
+$ cd /
+$ mkdir old returns
+$ mv 'My Documents/tax return.pdf' old returns/2022.pdf
+$ rm -r 'My Documents'
+$ find
+/
+/old returns
+/old returns/2022.pdf
+",
+    );
+
+    assert!(file_system.inconsistencies().is_empty());
+    assert_eq!(size(&file_system, "/old returns/2022.pdf"), 10);
+}
+
+#[test]
+fn test_inconsistencies() {
+    let file_system = replay(
+        "$ cd /
+$ ls
+dir a
+10 b
+$ cd c
+$ ls
+20 b
+$ rm a
+$ mkdir a
+$ pwd
+/a
+",
+    );
+
+    assert_eq!(
+        file_system.inconsistencies(),
+        &[
+            Inconsistency::UnknownDirectory {
+                command: 2,
+                path: "c".to_string()
+            },
+            Inconsistency::ConflictingSize {
+                command: 3,
+                path: "/b".to_string(),
+                previous: 10,
+                new: 20
+            },
+            Inconsistency::IsADirectory {
+                command: 4,
+                path: "a".to_string()
+            },
+            Inconsistency::AlreadyExists {
+                command: 5,
+                path: "a".to_string()
+            },
+            Inconsistency::OutputMismatch {
+                command: 6,
+                expected: vec!["/a".to_string()],
+                actual: vec!["/".to_string()]
+            },
+        ]
+    );
+}
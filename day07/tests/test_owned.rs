@@ -0,0 +1,98 @@
+use day07::owned::{format_size, Entry, EntryKind, FormatOptions};
+use day07::parser::parse_data;
+use day07::tree::generate_tree_from_commands;
+
+static TRANSCRIPT: &str = "$ cd /
+$ ls
+dir a
+2048 b
+$ cd a
+$ ls
+10 c
+dir d
+$ cd d
+$ ls
+1536 e
+";
+
+fn entry() -> Entry {
+    let commands = parse_data(TRANSCRIPT);
+    Entry::from_tree(&generate_tree_from_commands(&commands))
+}
+
+#[test]
+fn test_from_tree() {
+    let entry = entry();
+
+    assert_eq!(entry.size, 3594);
+    assert_eq!(entry.children[0].path, "/a");
+    assert_eq!(entry.children[0].children[1].children[0].path, "/a/d/e");
+    assert_eq!(entry.children[1].kind, EntryKind::File);
+
+    // The owned entry does not borrow the input anymore
+    assert_eq!(Entry::from_tree(&entry.to_tree()), entry);
+}
+
+#[test]
+fn test_json() {
+    let entry = entry();
+
+    let json = entry.to_json(&FormatOptions::default());
+    assert_eq!(serde_json::from_str::<Entry>(&json).unwrap(), entry);
+
+    let json = entry.to_json(&FormatOptions {
+        max_depth: Some(0),
+        ..FormatOptions::default()
+    });
+    assert_eq!(
+        json,
+        r#"{
+  "kind": "dir",
+  "name": "/",
+  "path": "/",
+  "size": 3594
+}"#
+    );
+}
+
+#[test]
+fn test_du() {
+    assert_eq!(
+        entry().to_du(&FormatOptions {
+            human_readable: true,
+            max_depth: Some(2),
+        }),
+        "3.5K\t/
+2.0K\t/b
+1.5K\t/a
+1.5K\t/a/d
+10\t/a/c
+"
+    );
+}
+
+#[test]
+fn test_tree_view() {
+    assert_eq!(
+        entry().to_tree_view(&FormatOptions::default()),
+        "/ (3594)
+├── a (1546)
+│   ├── c (10)
+│   └── d (1536)
+│       └── e (1536)
+└── b (2048)
+"
+    );
+}
+
+#[test]
+fn test_format_size() {
+    assert_eq!(format_size(1000, true), "1000");
+    assert_eq!(format_size(1536, true), "1.5K");
+    assert_eq!(format_size(48381165, true), "46M");
+    assert_eq!(format_size(48381165, false), "48381165");
+    // Rounded before choosing the unit
+    assert_eq!(format_size(1048575, true), "1.0M");
+    assert_eq!(format_size(10200, true), "10K");
+    assert_eq!(format_size(1023 * 1024, true), "1023K");
+}
@@ -1,6 +1,10 @@
-use day07::model::{CdTarget, Command, DirEntry, FileEntry, Inode};
+use std::cmp::Ordering;
+
+use day07::model::{CdTarget, Command, DirEntry, FileEntry, Inode, SizeFilter};
 use day07::parser::{
-    parse_cd_command, parse_dir_line, parse_file_line, parse_input, parse_ls_command, parse_ls_line,
+    parse_cd_command, parse_dir_line, parse_du_command, parse_file_line, parse_find_command,
+    parse_input, parse_ls_command, parse_ls_line, parse_mkdir_command, parse_mv_command,
+    parse_pwd_command, parse_rm_command,
 };
 
 #[test]
@@ -78,6 +82,70 @@ fn test_parse_cd_line() {
         parse_cd_command("$ cd a\n"),
         Ok(("", CdTarget::Directory("a")))
     );
+    assert_eq!(
+        parse_cd_command("$ cd a.b\n"),
+        Ok(("", CdTarget::Directory("a.b")))
+    );
+    assert_eq!(
+        parse_cd_command("$ cd /a/b\n"),
+        Ok(("", CdTarget::Directory("/a/b")))
+    );
+}
+
+#[test]
+fn test_parse_session_commands() {
+    assert_eq!(parse_pwd_command("$ pwd\n/a\n"), Ok(("", vec!["/a"])));
+    assert_eq!(parse_pwd_command("$ pwd\n$ ls\n"), Ok(("$ ls\n", vec![])));
+    assert_eq!(parse_mkdir_command("$ mkdir x.d\n"), Ok(("", "x.d")));
+    assert_eq!(parse_rm_command("$ rm -rf a/b\n"), Ok(("", ("a/b", true))));
+    assert_eq!(parse_rm_command("$ rm b.txt\n"), Ok(("", ("b.txt", false))));
+    assert_eq!(parse_mv_command("$ mv a ../b\n"), Ok(("", ("a", "../b"))));
+    assert_eq!(
+        parse_mkdir_command("$ mkdir My Documents\n"),
+        Ok(("", "My Documents"))
+    );
+    assert_eq!(
+        parse_rm_command("$ rm -r 'My Documents'\n"),
+        Ok(("", ("My Documents", true)))
+    );
+    assert_eq!(
+        parse_mv_command("$ mv \"My Documents/tax return.pdf\" old returns\n"),
+        Ok(("", ("My Documents/tax return.pdf", "old returns")))
+    );
+    assert_eq!(
+        parse_du_command("$ du /a\n584 /a/e\n94853 /a\n"),
+        Ok(("", (Some("/a"), vec!["584 /a/e", "94853 /a"])))
+    );
+    assert_eq!(
+        parse_find_command("$ find -size +100000\n/b.txt\n"),
+        Ok((
+            "",
+            (
+                None,
+                Some(SizeFilter {
+                    ordering: Ordering::Greater,
+                    value: 100000,
+                    unit: 512
+                }),
+                vec!["/b.txt"]
+            )
+        ))
+    );
+    assert_eq!(
+        parse_find_command("$ find a -size 584c\n"),
+        Ok((
+            "",
+            (
+                Some("a"),
+                Some(SizeFilter {
+                    ordering: Ordering::Equal,
+                    value: 584,
+                    unit: 1
+                }),
+                vec![]
+            )
+        ))
+    );
 }
 
 #[test]
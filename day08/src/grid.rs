@@ -1,24 +1,130 @@
+use std::fmt::{Display, Formatter};
+
+/// Index of the viewing directions in the arrays of viewing distances
+pub const LEFT: usize = 0;
+pub const RIGHT: usize = 1;
+pub const UP: usize = 2;
+pub const DOWN: usize = 3;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum GridError {
+    /// The input holds no tree
+    Empty,
+    /// A line holds something else than heights
+    InvalidHeight { line: usize, content: String },
+    /// A line of trees does not have the width of the first one
+    RaggedLine {
+        line: usize,
+        width: usize,
+        expected: usize,
+    },
+}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "the forest has no tree"),
+            GridError::InvalidHeight { line, content } => {
+                write!(f, "line {}: invalid tree heights '{}'", line, content)
+            }
+            GridError::RaggedLine {
+                line,
+                width,
+                expected,
+            } => write!(
+                f,
+                "line {} has {} trees instead of {}",
+                line, width, expected
+            ),
+        }
+    }
+}
+
 pub struct Grid {
-    data: Vec<Vec<u8>>,
+    data: Vec<Vec<u32>>,
     width: usize,
     height: usize,
 }
 
+/// For every tree of a line, how far it can see towards the start of the line and whether it is
+/// visible from that side of the forest.
+///
+/// The sweep keeps a stack of the trees that are not hidden by a closer tree yet, their heights
+/// strictly decreasing from the bottom to the top of the stack. Each tree pops the smaller trees,
+/// the top of the stack is then the tree blocking its view. Every tree is pushed and popped at
+/// most once, so the sweep is linear.
+fn sweep_line(heights: &[u32]) -> Vec<(usize, bool)> {
+    let mut stack: Vec<usize> = vec![];
+
+    heights
+        .iter()
+        .enumerate()
+        .map(|(position, height)| {
+            while stack.last().is_some_and(|&other| heights[other] < *height) {
+                stack.pop();
+            }
+
+            let sight = match stack.last() {
+                None => (position, true),
+                Some(&other) => (position - other, false),
+            };
+            stack.push(position);
+
+            sight
+        })
+        .collect()
+}
+
+/// Scenic score of every tree from its viewing distances, as computed by `Grid::sights`
+pub fn scenic_scores(distances: &[Vec<[usize; 4]>]) -> Vec<Vec<usize>> {
+    distances
+        .iter()
+        .map(|line| {
+            line.iter()
+                .map(|distances| distances.iter().product())
+                .collect()
+        })
+        .collect()
+}
+
 impl Grid {
-    pub fn from_str(data: &str) -> Grid {
-        let data: Vec<Vec<u8>> = data
+    /// Parse a grid with one digit per tree, or with comma separated heights on every line
+    pub fn parse(data: &str) -> Result<Grid, GridError> {
+        let data: Vec<Vec<u32>> = data
             .lines()
-            .map(|line| line.chars().map(|char| char as u8 - 48).collect())
-            .collect();
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let heights: Option<Vec<u32>> = if line.contains(',') {
+                    line.split(',')
+                        .map(|height| height.trim().parse().ok())
+                        .collect()
+                } else {
+                    line.trim().chars().map(|char| char.to_digit(10)).collect()
+                };
+                heights.ok_or_else(|| GridError::InvalidHeight {
+                    line: index + 1,
+                    content: line.to_string(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
 
-        let width = data[0].len();
+        let width = data.first().ok_or(GridError::Empty)?.len();
         let height = data.len();
 
-        Grid {
+        if let Some(line) = data.iter().position(|line| line.len() != width) {
+            return Err(GridError::RaggedLine {
+                line: line + 1,
+                width: data[line].len(),
+                expected: width,
+            });
+        }
+
+        Ok(Grid {
             data,
             width,
             height,
-        }
+        })
     }
 
     #[inline(always)]
@@ -32,7 +138,7 @@ impl Grid {
     }
 
     #[inline(always)]
-    fn get(&self, line: usize, column: usize) -> u8 {
+    pub fn get(&self, line: usize, column: usize) -> u32 {
         self.data[line][column]
     }
 
@@ -45,6 +151,59 @@ impl Grid {
         }
     }
 
+    /// Compute, for every tree, its viewing distance in every direction and whether it is
+    /// visible from outside the forest, with one sweep per line and column and per direction.
+    pub fn sights(&self) -> (Vec<Vec<[usize; 4]>>, Vec<Vec<bool>>) {
+        let mut distances = vec![vec![[0; 4]; self.width]; self.height];
+        let mut visible = vec![vec![false; self.width]; self.height];
+
+        let mut apply = |line: usize, column: usize, direction: usize, sight: (usize, bool)| {
+            distances[line][column][direction] = sight.0;
+            visible[line][column] |= sight.1;
+        };
+
+        for line in 0..self.height {
+            let mut heights = self.data[line].clone();
+            for (column, sight) in sweep_line(&heights).into_iter().enumerate() {
+                apply(line, column, LEFT, sight);
+            }
+            heights.reverse();
+            for (column, sight) in sweep_line(&heights).into_iter().enumerate() {
+                apply(line, self.width - 1 - column, RIGHT, sight);
+            }
+        }
+
+        for column in 0..self.width {
+            let mut heights: Vec<u32> = (0..self.height)
+                .map(|line| self.get(line, column))
+                .collect();
+            for (line, sight) in sweep_line(&heights).into_iter().enumerate() {
+                apply(line, column, UP, sight);
+            }
+            heights.reverse();
+            for (line, sight) in sweep_line(&heights).into_iter().enumerate() {
+                apply(self.height - 1 - line, column, DOWN, sight);
+            }
+        }
+
+        (distances, visible)
+    }
+
+    /// Viewing distances of every tree, in the `LEFT`, `RIGHT`, `UP` and `DOWN` order
+    pub fn viewing_distances(&self) -> Vec<Vec<[usize; 4]>> {
+        self.sights().0
+    }
+
+    /// Whether every tree is visible from outside the forest
+    pub fn visibility_map(&self) -> Vec<Vec<bool>> {
+        self.sights().1
+    }
+
+    /// Scenic score of every tree
+    pub fn scenic_scores(&self) -> Vec<Vec<usize>> {
+        scenic_scores(&self.viewing_distances())
+    }
+
     pub fn is_visible(&self, line: usize, column: usize) -> bool {
         let tree_height = self.get(line, column);
 
@@ -0,0 +1,196 @@
+use std::fmt::Write as _;
+use std::io::Write;
+
+use crate::grid::Grid;
+
+/// The value of the trees displayed by a heatmap
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Layer {
+    Height,
+    Visibility,
+    ScenicScore,
+}
+
+/// Color used to mark the best treehouse
+const MARKER_COLOR: (u8, u8, u8) = (255, 255, 255);
+
+/// Stops of the color gradient, from the lowest to the highest value
+const GRADIENT: [(u8, u8, u8); 5] = [
+    (0, 0, 95),
+    (0, 135, 255),
+    (0, 215, 0),
+    (255, 215, 0),
+    (215, 0, 0),
+];
+
+/// Values of the trees of a grid scaled to `[0, 1]`, ready to be drawn with a color gradient
+pub struct Heatmap {
+    values: Vec<Vec<f64>>,
+    marker: Option<(usize, usize)>,
+}
+
+impl Heatmap {
+    pub fn new(grid: &Grid, layer: Layer) -> Self {
+        let raw: Vec<Vec<f64>> = match layer {
+            Layer::Height => (0..grid.height())
+                .map(|line| {
+                    (0..grid.width())
+                        .map(|column| grid.get(line, column) as f64)
+                        .collect()
+                })
+                .collect(),
+            Layer::Visibility => grid
+                .visibility_map()
+                .into_iter()
+                .map(|line| {
+                    line.into_iter()
+                        .map(|visible| visible as u8 as f64)
+                        .collect()
+                })
+                .collect(),
+            Layer::ScenicScore => grid
+                .scenic_scores()
+                .into_iter()
+                .map(|line| line.into_iter().map(|score| score as f64).collect())
+                .collect(),
+        };
+
+        let max = raw.iter().flatten().cloned().fold(0.0, f64::max);
+        let values = raw
+            .into_iter()
+            .map(|line| {
+                line.into_iter()
+                    .map(|value| if max > 0.0 { value / max } else { 0.0 })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            values,
+            marker: None,
+        }
+    }
+
+    /// Mark the tree at `(line, column)`
+    pub fn with_marker(self, line: usize, column: usize) -> Self {
+        Self {
+            marker: Some((line, column)),
+            ..self
+        }
+    }
+
+    /// Mark the tree with the best scenic score
+    pub fn with_best_treehouse(self, grid: &Grid) -> Self {
+        match grid.best_treehouses(1).first() {
+            Some(treehouse) => self.with_marker(treehouse.line, treehouse.column),
+            None => self,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.values.first().map_or(0, |line| line.len())
+    }
+
+    pub fn height(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Value of the tree at `(line, column)`, between 0 and 1
+    pub fn value(&self, line: usize, column: usize) -> f64 {
+        self.values[line][column]
+    }
+
+    /// Draw the heatmap with two characters per tree using ANSI 256 colors backgrounds. The
+    /// marked tree is drawn as `<>`.
+    pub fn to_ansi(&self) -> String {
+        let mut output = String::new();
+        for (line, values) in self.values.iter().enumerate() {
+            for (column, value) in values.iter().enumerate() {
+                let background = ansi_color(gradient(*value));
+                if self.marker == Some((line, column)) {
+                    let foreground = ansi_color(MARKER_COLOR);
+                    let _ = write!(
+                        output,
+                        "\x1b[48;5;{}m\x1b[38;5;{}m<>",
+                        background, foreground
+                    );
+                } else {
+                    let _ = write!(output, "\x1b[48;5;{}m  ", background);
+                }
+            }
+            output.push_str("\x1b[0m\n");
+        }
+
+        output
+    }
+
+    /// RGB pixels of the heatmap drawn with `scale` pixels per tree. The marked tree is outlined,
+    /// or filled when it is too small to be outlined.
+    pub fn pixels(&self, scale: usize) -> Vec<u8> {
+        let scale = scale.max(1);
+        let mut pixels = Vec::with_capacity(self.width() * self.height() * scale * scale * 3);
+
+        for y in 0..self.height() * scale {
+            for x in 0..self.width() * scale {
+                let (line, column) = (y / scale, x / scale);
+                let on_border = [x % scale, y % scale]
+                    .iter()
+                    .any(|offset| *offset == 0 || *offset == scale - 1);
+                let color = if self.marker == Some((line, column)) && (scale < 3 || on_border) {
+                    MARKER_COLOR
+                } else {
+                    gradient(self.values[line][column])
+                };
+                pixels.extend([color.0, color.1, color.2]);
+            }
+        }
+
+        pixels
+    }
+
+    /// Write the heatmap as a binary PPM image
+    pub fn write_ppm(&self, writer: &mut impl Write, scale: usize) -> std::io::Result<()> {
+        let scale = scale.max(1);
+        write!(
+            writer,
+            "P6\n{} {}\n255\n",
+            self.width() * scale,
+            self.height() * scale
+        )?;
+        writer.write_all(&self.pixels(scale))
+    }
+
+    /// Write the heatmap as a PNG image
+    pub fn write_png(&self, writer: impl Write, scale: usize) -> Result<(), png::EncodingError> {
+        let scale = scale.max(1);
+        let mut encoder = png::Encoder::new(
+            writer,
+            (self.width() * scale) as u32,
+            (self.height() * scale) as u32,
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels(scale))
+    }
+}
+
+/// Color of a value between 0 and 1, interpolated between the stops of the gradient
+pub fn gradient(value: f64) -> (u8, u8, u8) {
+    let position = value.clamp(0.0, 1.0) * (GRADIENT.len() - 1) as f64;
+    let index = (position.floor() as usize).min(GRADIENT.len() - 2);
+    let ratio = position - index as f64;
+
+    let (from, to) = (GRADIENT[index], GRADIENT[index + 1]);
+    let mix = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * ratio).round() as u8;
+
+    (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+/// Closest color of the 6x6x6 cube of the ANSI 256 colors palette
+pub fn ansi_color(color: (u8, u8, u8)) -> u8 {
+    let level = |component: u8| (component as f64 / 255.0 * 5.0).round() as u8;
+
+    16 + 36 * level(color.0) + 6 * level(color.1) + level(color.2)
+}
@@ -0,0 +1,3 @@
+pub mod grid;
+pub mod heatmap;
+pub mod sight;
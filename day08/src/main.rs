@@ -1,36 +1,92 @@
-use std::fs;
+use std::fs::{self, File};
+use std::io::BufWriter;
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
-use crate::grid::Grid;
+use day08::grid::{scenic_scores, Grid, DOWN, LEFT, RIGHT, UP};
+use day08::heatmap::{Heatmap, Layer};
 
-mod grid;
+#[derive(ValueEnum, Debug, Eq, PartialEq, Clone, Copy)]
+enum HeatmapLayer {
+    Height,
+    Visibility,
+    Scenic,
+}
+
+impl From<HeatmapLayer> for Layer {
+    fn from(layer: HeatmapLayer) -> Self {
+        match layer {
+            HeatmapLayer::Height => Layer::Height,
+            HeatmapLayer::Visibility => Layer::Visibility,
+            HeatmapLayer::Scenic => Layer::ScenicScore,
+        }
+    }
+}
 
-#[derive(Parser, Debug, Eq, PartialEq, Clone)]
+#[derive(Parser, Debug, PartialEq, Clone)]
 struct Cli {
     /// Enable verbose display
     #[arg(short, long, default_value_t = false)]
     verbose: bool,
 
+    /// Count the trees visible from the top of the tree at LINE,COLUMN
+    #[arg(long, value_parser = parse_position)]
+    from: Option<(usize, usize)>,
+
+    /// Count the trees visible from an observer outside the forest in this direction, in
+    /// degrees counterclockwise from the right of the grid
+    #[arg(long, allow_negative_numbers = true)]
+    angle: Option<f64>,
+
+    /// Display the best treehouse sites
+    #[arg(short, long, default_value_t = 0)]
+    top: usize,
+
+    /// Display a heatmap of a value of the trees
+    #[arg(long, value_enum)]
+    heatmap: Option<HeatmapLayer>,
+
+    /// Export the heatmap to a .ppm or .png image instead of displaying it
+    #[arg(short, long, requires = "heatmap")]
+    output: Option<PathBuf>,
+
+    /// Size in pixels of a tree in the exported heatmap
+    #[arg(long, default_value_t = 8)]
+    scale: usize,
+
+    /// Mark the best treehouse location on the heatmap
+    #[arg(long, default_value_t = false)]
+    mark_best: bool,
+
     /// File to parse
     #[arg(default_value = "input")]
     path: PathBuf,
 }
 
-fn solve_part_one(data: &Grid) {
-    let res: usize = (0..data.height())
-        .flat_map(|line| {
-            (0..data.width()).map(move |column| data.is_visible(line, column) as usize)
-        })
-        .sum();
+fn parse_position(value: &str) -> Result<(usize, usize), String> {
+    let (line, column) = value
+        .split_once(',')
+        .ok_or_else(|| format!("expected LINE,COLUMN, got {}", value))?;
+    let parse = |coordinate: &str| coordinate.trim().parse().map_err(|err| format!("{}", err));
+
+    Ok((parse(line)?, parse(column)?))
+}
+
+fn count_visible(map: &[Vec<bool>]) -> usize {
+    map.iter().flatten().filter(|visible| **visible).count()
+}
+
+fn solve_part_one(visible: &[Vec<bool>]) {
+    let res: usize = count_visible(visible);
 
     println!("Part one solution: {:#?}", res);
 }
 
-fn solve_part_two(data: &Grid) {
-    let res: usize = (0..data.height())
-        .flat_map(|line| (0..data.width()).map(move |column| data.scenic_score(line, column)))
+fn solve_part_two(distances: &[Vec<[usize; 4]>]) {
+    let res: usize = scenic_scores(distances)
+        .into_iter()
+        .flatten()
         .max()
         .unwrap();
 
@@ -41,12 +97,67 @@ fn main() {
     let args = Cli::parse();
 
     let data: String = fs::read_to_string(&args.path).unwrap();
-    let grid = Grid::from_str(&data);
+    let grid = match Grid::parse(&data) {
+        Ok(grid) => grid,
+        Err(error) => {
+            eprintln!("Unable to parse {}: {}", args.path.display(), error);
+            std::process::exit(1);
+        }
+    };
 
     if args.verbose {
         grid.pretty_print();
     }
 
-    solve_part_one(&grid);
-    solve_part_two(&grid);
+    // Both parts come from the same sweeps
+    let (distances, visible) = grid.sights();
+    solve_part_one(&visible);
+    solve_part_two(&distances);
+
+    if let Some((line, column)) = args.from {
+        let distances = grid.viewing_distance(line, column);
+        println!(
+            "Trees visible from ({}, {}): {}, viewing distances left {} right {} up {} down {}",
+            line,
+            column,
+            count_visible(&grid.visible_from(line, column)),
+            distances[LEFT],
+            distances[RIGHT],
+            distances[UP],
+            distances[DOWN]
+        );
+    }
+
+    if let Some(angle) = args.angle {
+        println!(
+            "Trees visible from {} degrees: {}",
+            angle,
+            count_visible(&grid.visible_from_angle(angle))
+        );
+    }
+
+    for treehouse in grid.best_treehouses(args.top) {
+        println!(
+            "Treehouse at ({}, {}): scenic score {}",
+            treehouse.line, treehouse.column, treehouse.score
+        );
+    }
+
+    if let Some(layer) = args.heatmap {
+        let mut heatmap = Heatmap::new(&grid, layer.into());
+        if args.mark_best {
+            heatmap = heatmap.with_best_treehouse(&grid);
+        }
+
+        match &args.output {
+            None => print!("{}", heatmap.to_ansi()),
+            Some(output) => {
+                let mut writer = BufWriter::new(File::create(output).unwrap());
+                match output.extension().and_then(|extension| extension.to_str()) {
+                    Some("png") => heatmap.write_png(&mut writer, args.scale).unwrap(),
+                    _ => heatmap.write_ppm(&mut writer, args.scale).unwrap(),
+                }
+            }
+        }
+    }
 }
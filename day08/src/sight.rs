@@ -0,0 +1,194 @@
+use std::cmp::Reverse;
+
+use crate::grid::{Grid, DOWN, LEFT, RIGHT, UP};
+
+/// Tolerance used to detect rays going exactly through the corner of a cell
+const EPSILON: f64 = 1e-9;
+
+/// A tree on which a treehouse can be built
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Treehouse {
+    pub line: usize,
+    pub column: usize,
+    pub score: usize,
+}
+
+impl Grid {
+    /// Walk the cells crossed by the ray starting at the center of the cell `(line, column)` and
+    /// going in the `(dx, dy)` direction, `dx` going along the columns and `dy` along the lines.
+    ///
+    /// The start cell is not visited. `visit` is called with every other cell crossed by the ray
+    /// and the ray parameters where the ray enters and exits it, until it returns `false` or the
+    /// ray leaves the grid. A ray going exactly through the corner of a cell goes directly to the
+    /// diagonal cell.
+    fn walk_ray(
+        &self,
+        line: usize,
+        column: usize,
+        dx: f64,
+        dy: f64,
+        mut visit: impl FnMut(usize, usize, f64, f64) -> bool,
+    ) {
+        let step_x: isize = if dx < 0.0 { -1 } else { 1 };
+        let step_y: isize = if dy < 0.0 { -1 } else { 1 };
+        let delta_x = 1.0 / dx.abs();
+        let delta_y = 1.0 / dy.abs();
+
+        // The first cell boundaries are half a cell away from the center
+        let mut next_x = delta_x / 2.0;
+        let mut next_y = delta_y / 2.0;
+        let mut x = column as isize;
+        let mut y = line as isize;
+
+        loop {
+            let enter = next_x.min(next_y);
+            if (next_x - next_y).abs() < EPSILON {
+                x += step_x;
+                y += step_y;
+                next_x += delta_x;
+                next_y += delta_y;
+            } else if next_x < next_y {
+                x += step_x;
+                next_x += delta_x;
+            } else {
+                y += step_y;
+                next_y += delta_y;
+            }
+
+            if x < 0 || y < 0 || x as usize >= self.width() || y as usize >= self.height() {
+                return;
+            }
+            if !visit(y as usize, x as usize, enter, next_x.min(next_y)) {
+                return;
+            }
+        }
+    }
+
+    /// Whether the top of the tree `to` can be seen from the top of the tree `from`, both given
+    /// as `(line, column)`.
+    ///
+    /// The sight line goes from the center of the top of a tree to the center of the top of the
+    /// other one. A tree in between hides the target if it reaches the sight line somewhere
+    /// above its cell.
+    pub fn can_see(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        if from == to {
+            return true;
+        }
+
+        let from_height = self.get(from.0, from.1) as f64;
+        let to_height = self.get(to.0, to.1) as f64;
+        let sight_height = |t: f64| from_height + t * (to_height - from_height);
+
+        let mut visible = true;
+        self.walk_ray(
+            from.0,
+            from.1,
+            to.1 as f64 - from.1 as f64,
+            to.0 as f64 - from.0 as f64,
+            |line, column, enter, exit| {
+                if (line, column) == to || enter > 1.0 + EPSILON {
+                    return false;
+                }
+
+                let lowest_sight = sight_height(enter).min(sight_height(exit.min(1.0)));
+                if self.get(line, column) as f64 >= lowest_sight {
+                    visible = false;
+                }
+
+                visible
+            },
+        );
+
+        visible
+    }
+
+    /// Trees whose top can be seen from the top of the tree at `(line, column)`, see `can_see`.
+    /// The tree itself is not included.
+    pub fn visible_from(&self, line: usize, column: usize) -> Vec<Vec<bool>> {
+        (0..self.height())
+            .map(|other_line| {
+                (0..self.width())
+                    .map(|other_column| {
+                        (other_line, other_column) != (line, column)
+                            && self.can_see((line, column), (other_line, other_column))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Trees visible from an observer standing far away outside the forest, in the direction
+    /// given by `angle` in degrees: 0 is on the right of the grid, 90 above it, 180 on its left
+    /// and 270 below it.
+    ///
+    /// Like from the edges of the forest, a tree is visible if the trees between it and the
+    /// observer are all shorter than it.
+    pub fn visible_from_angle(&self, angle: f64) -> Vec<Vec<bool>> {
+        let (sin, cos) = angle.to_radians().sin_cos();
+
+        (0..self.height())
+            .map(|line| {
+                (0..self.width())
+                    .map(|column| {
+                        let height = self.get(line, column);
+                        let mut visible = true;
+                        self.walk_ray(line, column, cos, -sin, |other_line, other_column, _, _| {
+                            visible = self.get(other_line, other_column) < height;
+                            visible
+                        });
+
+                        visible
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Viewing distances of the tree at `(line, column)`, in the `LEFT`, `RIGHT`, `UP` and `DOWN`
+    /// order
+    pub fn viewing_distance(&self, line: usize, column: usize) -> [usize; 4] {
+        let height = self.get(line, column);
+        let distance = |trees: &mut dyn Iterator<Item = u32>| {
+            let mut count = 0;
+            for other_height in trees {
+                count += 1;
+                if other_height >= height {
+                    break;
+                }
+            }
+            count
+        };
+
+        let mut distances = [0; 4];
+        distances[LEFT] = distance(&mut (0..column).rev().map(|i| self.get(line, i)));
+        distances[RIGHT] = distance(&mut (column + 1..self.width()).map(|i| self.get(line, i)));
+        distances[UP] = distance(&mut (0..line).rev().map(|i| self.get(i, column)));
+        distances[DOWN] = distance(&mut (line + 1..self.height()).map(|i| self.get(i, column)));
+
+        distances
+    }
+
+    /// The `count` trees with the best scenic scores, best first
+    pub fn best_treehouses(&self, count: usize) -> Vec<Treehouse> {
+        let mut treehouses: Vec<Treehouse> = self
+            .scenic_scores()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(line, scores)| {
+                scores
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(column, score)| Treehouse {
+                        line,
+                        column,
+                        score,
+                    })
+            })
+            .collect();
+
+        treehouses.sort_by_key(|treehouse| Reverse(treehouse.score));
+        treehouses.truncate(count);
+
+        treehouses
+    }
+}
@@ -0,0 +1,85 @@
+use day08::grid::{Grid, GridError};
+
+static INPUT_EXAMPLE: &str = "30373
+25512
+65332
+33549
+35390
+";
+
+#[test]
+fn test_visibility_map() {
+    let grid = Grid::parse(INPUT_EXAMPLE).unwrap();
+
+    assert_eq!(
+        grid.visibility_map(),
+        vec![
+            vec![true, true, true, true, true],
+            vec![true, true, true, false, true],
+            vec![true, true, false, true, true],
+            vec![true, false, true, false, true],
+            vec![true, true, true, true, true],
+        ]
+    );
+}
+
+#[test]
+fn test_scenic_scores() {
+    let grid = Grid::parse(INPUT_EXAMPLE).unwrap();
+    let scores = grid.scenic_scores();
+
+    assert_eq!(scores[1][2], 4);
+    assert_eq!(scores[3][2], 8);
+    assert_eq!(scores.iter().flatten().max(), Some(&8));
+}
+
+#[test]
+fn test_viewing_distances() {
+    let grid = Grid::parse(INPUT_EXAMPLE).unwrap();
+
+    // Left, right, up and down
+    assert_eq!(grid.viewing_distances()[3][2], [2, 2, 2, 1]);
+}
+
+#[test]
+fn test_matches_per_tree_computation() {
+    let grid = Grid::parse(
+        "12,3,7,7,1,0,25
+4,15,15,2,8,9,1
+0,0,30,2,2,14,6
+",
+    )
+    .unwrap();
+    assert_eq!(grid.width(), 7);
+    assert_eq!(grid.height(), 3);
+    assert_eq!(grid.get(2, 2), 30);
+
+    let visible = grid.visibility_map();
+    let scores = grid.scenic_scores();
+    for line in 0..grid.height() {
+        for column in 0..grid.width() {
+            assert_eq!(visible[line][column], grid.is_visible(line, column));
+            assert_eq!(scores[line][column], grid.scenic_score(line, column));
+        }
+    }
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(
+        Grid::parse("123\n45\n").err(),
+        Some(GridError::RaggedLine {
+            line: 2,
+            width: 2,
+            expected: 3
+        })
+    );
+    assert_eq!(Grid::parse("\n").err(), Some(GridError::Empty));
+    assert_eq!(
+        Grid::parse("123\n4a6\n").err(),
+        Some(GridError::InvalidHeight {
+            line: 2,
+            content: "4a6".to_string()
+        })
+    );
+}
@@ -0,0 +1,86 @@
+use day08::grid::Grid;
+use day08::heatmap::{ansi_color, gradient, Heatmap, Layer};
+
+static INPUT_EXAMPLE: &str = "30373
+25512
+65332
+33549
+35390
+";
+
+#[test]
+fn test_gradient() {
+    assert_eq!(gradient(0.0), (0, 0, 95));
+    assert_eq!(gradient(0.5), (0, 215, 0));
+    assert_eq!(gradient(1.0), (215, 0, 0));
+    assert_eq!(gradient(2.0), (215, 0, 0));
+}
+
+#[test]
+fn test_ansi_color() {
+    assert_eq!(ansi_color((0, 0, 0)), 16);
+    assert_eq!(ansi_color((255, 255, 255)), 231);
+    assert_eq!(ansi_color((255, 0, 0)), 196);
+}
+
+#[test]
+fn test_values() {
+    let grid = Grid::parse(INPUT_EXAMPLE).unwrap();
+
+    let heights = Heatmap::new(&grid, Layer::Height);
+    assert_eq!(heights.value(3, 4), 1.0);
+    assert_eq!(heights.value(1, 0), 2.0 / 9.0);
+
+    let visibility = Heatmap::new(&grid, Layer::Visibility);
+    assert_eq!(visibility.value(1, 3), 0.0);
+    assert_eq!(visibility.value(1, 2), 1.0);
+
+    let scores = Heatmap::new(&grid, Layer::ScenicScore);
+    assert_eq!(scores.value(3, 2), 1.0);
+    assert_eq!(scores.value(1, 2), 0.5);
+}
+
+#[test]
+fn test_to_ansi() {
+    let grid = Grid::parse("19\n").unwrap();
+    let heatmap = Heatmap::new(&grid, Layer::Height).with_best_treehouse(&grid);
+
+    // The 1 is marked, both trees have a scenic score of 0
+    assert_eq!(
+        heatmap.to_ansi(),
+        "\x1b[48;5;25m\x1b[38;5;231m<>\x1b[48;5;160m  \x1b[0m\n"
+    );
+}
+
+#[test]
+fn test_write_ppm() {
+    let grid = Grid::parse(INPUT_EXAMPLE).unwrap();
+    let heatmap = Heatmap::new(&grid, Layer::ScenicScore).with_best_treehouse(&grid);
+
+    let mut output = vec![];
+    heatmap.write_ppm(&mut output, 4).unwrap();
+
+    let header = b"P6\n20 20\n255\n";
+    assert_eq!(&output[..header.len()], header);
+    assert_eq!(output.len(), header.len() + 20 * 20 * 3);
+
+    // The best treehouse is outlined in white, its inside keeps the heatmap color
+    let pixel = |x: usize, y: usize| {
+        let start = header.len() + (y * 20 + x) * 3;
+        (output[start], output[start + 1], output[start + 2])
+    };
+    assert_eq!(pixel(8, 12), (255, 255, 255));
+    assert_eq!(pixel(9, 13), gradient(1.0));
+    assert_eq!(pixel(0, 0), gradient(0.0));
+}
+
+#[test]
+fn test_write_png() {
+    let grid = Grid::parse(INPUT_EXAMPLE).unwrap();
+    let heatmap = Heatmap::new(&grid, Layer::Height);
+
+    let mut output = vec![];
+    heatmap.write_png(&mut output, 2).unwrap();
+
+    assert_eq!(&output[..8], b"\x89PNG\r\n\x1a\n");
+}
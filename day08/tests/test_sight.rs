@@ -0,0 +1,127 @@
+use day08::grid::Grid;
+use day08::sight::Treehouse;
+
+static INPUT_EXAMPLE: &str = "30373
+25512
+65332
+33549
+35390
+";
+
+#[test]
+fn test_can_see() {
+    let grid = Grid::parse(INPUT_EXAMPLE).unwrap();
+
+    // Direct neighbours are always visible
+    assert!(grid.can_see((3, 2), (3, 1)));
+    assert!(grid.can_see((3, 2), (2, 2)));
+    // The 4 in between is below the sight line going from 5 to 9
+    assert!(grid.can_see((3, 2), (3, 4)));
+    // The 5 in between hides the 3 behind it
+    assert!(!grid.can_see((3, 2), (0, 2)));
+    // The 5 in between does not hide the 5 behind it
+    assert!(grid.can_see((3, 2), (1, 2)));
+}
+
+#[test]
+fn test_can_see_is_symmetric() {
+    let grid = Grid::parse(INPUT_EXAMPLE).unwrap();
+
+    for from in 0..25 {
+        for to in 0..25 {
+            let from = (from / 5, from % 5);
+            let to = (to / 5, to % 5);
+            assert_eq!(grid.can_see(from, to), grid.can_see(to, from));
+        }
+    }
+}
+
+#[test]
+fn test_visible_from() {
+    let grid = Grid::parse(INPUT_EXAMPLE).unwrap();
+    let visible = grid.visible_from(3, 2);
+
+    assert!(!visible[3][2]);
+    assert!(visible[3][0]);
+    assert!(visible[4][2]);
+    assert!(!visible[0][2]);
+}
+
+#[test]
+fn test_visible_from_axes() {
+    let grid = Grid::parse(INPUT_EXAMPLE).unwrap();
+    let maps: Vec<Vec<Vec<bool>>> = [0.0, 90.0, 180.0, 270.0]
+        .iter()
+        .map(|angle| grid.visible_from_angle(*angle))
+        .collect();
+
+    let combined: Vec<Vec<bool>> = (0..5)
+        .map(|line| {
+            (0..5)
+                .map(|column| maps.iter().any(|map| map[line][column]))
+                .collect()
+        })
+        .collect();
+    assert_eq!(combined, grid.visibility_map());
+
+    // From the right, only the trees taller than every tree on their right are visible
+    assert_eq!(maps[0][3], vec![false, false, false, false, true]);
+}
+
+#[test]
+fn test_visible_from_diagonal() {
+    let grid = Grid::parse(
+        "111
+151
+119
+",
+    )
+    .unwrap();
+
+    // From the bottom right corner the 5 is hidden by the 9
+    let visible = grid.visible_from_angle(-45.0);
+    assert!(!visible[1][1]);
+    assert!(visible[2][2]);
+    // The 1 on the left of the 9 is on the edge from this angle
+    assert!(visible[2][1]);
+
+    // From the top left corner, the 5 is only behind a 1
+    assert!(grid.visible_from_angle(135.0)[1][1]);
+}
+
+#[test]
+fn test_viewing_distance() {
+    let grid = Grid::parse(INPUT_EXAMPLE).unwrap();
+
+    assert_eq!(grid.viewing_distance(1, 2), [1, 2, 1, 2]);
+    assert_eq!(grid.viewing_distance(3, 2), [2, 2, 2, 1]);
+    for line in 0..5 {
+        for column in 0..5 {
+            assert_eq!(
+                grid.viewing_distance(line, column),
+                grid.viewing_distances()[line][column]
+            );
+        }
+    }
+}
+
+#[test]
+fn test_best_treehouses() {
+    let grid = Grid::parse(INPUT_EXAMPLE).unwrap();
+
+    assert_eq!(
+        grid.best_treehouses(2),
+        vec![
+            Treehouse {
+                line: 3,
+                column: 2,
+                score: 8
+            },
+            Treehouse {
+                line: 2,
+                column: 1,
+                score: 6
+            }
+        ]
+    );
+}
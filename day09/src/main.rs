@@ -1,156 +1,123 @@
-use std::collections::HashSet;
-use std::fmt::{Display, Formatter};
-use std::{env, fs};
-
-#[derive(Debug, Eq, PartialEq, Clone, Hash, Default)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::PathBuf;
 
-impl Point {
-    fn new() -> Self {
-        Self { x: 0, y: 0 }
-    }
-}
+use clap::builder::RangedU64ValueParser;
+use clap::{value_parser, Parser, ValueEnum};
 
-impl Display for Point {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{{x:{}, y:{}}}", self.x, self.y)
-    }
-}
-
-fn needs_to_move(a: &Point, b: &Point) -> bool {
-    (b.x - a.x).abs() >= 2 || (b.y - a.y).abs() >= 2
-}
+use day09::model::parse_commands;
+use day09::render::{replay, write_gif, write_text, Granularity};
+use day09::rope::{Rope, DEFAULT_FOLLOW_DISTANCE};
 
-#[derive(Debug, Eq, PartialEq, Clone)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+#[derive(ValueEnum, Debug, Eq, PartialEq, Clone, Copy)]
+enum RenderGranularity {
+    Step,
+    Command,
 }
 
-impl<'a> From<&'a str> for Direction {
-    fn from(value: &'a str) -> Self {
-        match value {
-            "U" => Self::Up,
-            "D" => Self::Down,
-            "R" => Self::Right,
-            "L" => Self::Left,
-            _ => unreachable!(),
+impl From<RenderGranularity> for Granularity {
+    fn from(granularity: RenderGranularity) -> Self {
+        match granularity {
+            RenderGranularity::Step => Granularity::Step,
+            RenderGranularity::Command => Granularity::Command,
         }
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Clone)]
-struct Command {
-    direction: Direction,
-    steps: i64,
+#[derive(Parser, Debug, Eq, PartialEq, Clone)]
+struct Cli {
+    /// Enable verbose display
+    #[arg(short, long, default_value_t = false)]
+    verbose: bool,
+
+    /// Number of knots of the rope, including the head
+    #[arg(
+        short,
+        long,
+        default_value_t = 10,
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    knots: usize,
+
+    /// Distance at which a knot starts following the previous one
+    #[arg(
+        short,
+        long,
+        default_value_t = DEFAULT_FOLLOW_DISTANCE,
+        value_parser = value_parser!(i32).range(1..)
+    )]
+    follow_distance: i32,
+
+    /// Draw the rope after every step or every command, followed by the positions visited by
+    /// the tail
+    #[arg(short, long, value_enum)]
+    render: Option<RenderGranularity>,
+
+    /// Write the rendered frames to this file instead of displaying them, as an animated GIF
+    /// if it ends with .gif and as text otherwise
+    #[arg(short, long, requires = "render")]
+    output: Option<PathBuf>,
+
+    /// Size in pixels of a cell of the GIF
+    #[arg(long, default_value_t = 4)]
+    scale: usize,
+
+    /// Delay between two frames of the GIF, in hundredths of a second
+    #[arg(long, default_value_t = 10)]
+    delay: u16,
+
+    /// File to parse
+    #[arg(default_value = "input")]
+    path: PathBuf,
 }
 
-impl<'a> From<&'a str> for Command {
-    fn from(value: &'a str) -> Self {
-        let (dir, steps) = value.split_once(" ").unwrap();
-
-        Self {
-            direction: Direction::from(dir),
-            steps: steps.parse().unwrap(),
-        }
-    }
-}
+fn main() {
+    let args = Cli::parse();
 
-fn solve_part_one(commands: &[Command]) {
-    let mut head = Point::new();
-    let mut tail = Point::new();
-    let mut tail_positions: HashSet<Point> = HashSet::new();
-    tail_positions.insert(tail.clone());
-
-    for command in commands {
-        for _ in 0..command.steps {
-            // Move the head
-            match command.direction {
-                Direction::Up => head.y += 1,
-                Direction::Down => head.y -= 1,
-                Direction::Left => head.x -= 1,
-                Direction::Right => head.x += 1,
-            }
+    let data: String = fs::read_to_string(&args.path).unwrap();
+    let commands = parse_commands(&data);
 
-            if needs_to_move(&tail, &head) {
-                if tail.x < head.x {
-                    tail.x += 1
-                } else if tail.x > head.x {
-                    tail.x -= 1
-                }
-
-                if tail.y < head.y {
-                    tail.y += 1
-                } else if tail.y > head.y {
-                    tail.y -= 1
-                }
-            }
+    // A knot only depends on the previous ones, so a single rope gives the answers for every
+    // shorter rope
+    let mut rope = Rope::new(args.knots, args.follow_distance);
+    rope.run(&commands);
+    let counts = rope.visit_counts();
 
-            // Add the point to the point history
-            tail_positions.insert(tail.clone());
+    if args.verbose {
+        for (index, count) in counts.iter().enumerate() {
+            println!("Knot {} visited {} positions", index, count);
         }
     }
 
-    println!("Part one solution: {}", tail_positions.len());
-}
+    if let Some(count) = counts.get(1) {
+        println!("Part one solution: {:#?}", count);
+    }
+    if let Some(count) = counts.get(9) {
+        println!("Part two solution: {:#?}", count);
+    }
 
-fn solve_part_two(commands: &[Command]) {
-    let mut knots: [Point; 10] = Default::default();
-    let mut tail_positions: HashSet<Point> = HashSet::new();
-    tail_positions.insert(knots[9].clone());
-
-    for command in commands {
-        for _ in 0..command.steps {
-            // Move the head
-            match command.direction {
-                Direction::Up => knots[0].y += 1,
-                Direction::Down => knots[0].y -= 1,
-                Direction::Left => knots[0].x -= 1,
-                Direction::Right => knots[0].x += 1,
+    if let Some(granularity) = args.render {
+        let frames = replay(
+            &commands,
+            args.knots,
+            args.follow_distance,
+            granularity.into(),
+        );
+
+        match &args.output {
+            None => write_text(frames, io::stdout().lock()).unwrap(),
+            Some(output)
+                if output
+                    .extension()
+                    .is_some_and(|extension| extension == "gif") =>
+            {
+                let writer = BufWriter::new(File::create(output).unwrap());
+                write_gif(frames, writer, args.scale, args.delay).unwrap();
             }
-
-            // for each knot following in the line
-            for i_knot in 0..9 {
-                if needs_to_move(&knots[i_knot + 1], &knots[i_knot]) {
-                    if &knots[i_knot + 1].x < &knots[i_knot].x {
-                        knots[i_knot + 1].x += 1
-                    } else if &knots[i_knot + 1].x > &knots[i_knot].x {
-                        knots[i_knot + 1].x -= 1
-                    }
-
-                    if &knots[i_knot + 1].y < &knots[i_knot].y {
-                        knots[i_knot + 1].y += 1
-                    } else if &knots[i_knot + 1].y > &knots[i_knot].y {
-                        knots[i_knot + 1].y -= 1
-                    }
-                }
+            Some(output) => {
+                let writer = BufWriter::new(File::create(output).unwrap());
+                write_text(frames, writer).unwrap();
             }
-
-            // Add the point to the point history
-            tail_positions.insert(knots[9].clone());
         }
     }
-
-    println!("Part one solution: {:#?}", tail_positions.len());
-}
-
-fn main() {
-    let args: Vec<_> = env::args().collect();
-
-    let file_path = if args.len() > 1 {
-        args[1].clone()
-    } else {
-        "input".to_string()
-    };
-
-    let data: String = fs::read_to_string(&file_path).unwrap();
-    let commands: Vec<Command> = data.lines().map(From::from).collect();
-
-    solve_part_one(&commands);
-    solve_part_two(&commands);
 }
@@ -0,0 +1,114 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, Default)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new() -> Self {
+        Self { x: 0, y: 0 }
+    }
+
+    /// Number of king moves between the two points
+    pub fn distance(&self, other: &Point) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{x:{}, y:{}}}", self.x, self.y)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    /// Move of one step in this direction, `y` going up
+    pub fn offset(&self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, 1),
+            Direction::Down => (0, -1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::UpLeft => (-1, 1),
+            Direction::UpRight => (1, 1),
+            Direction::DownLeft => (-1, -1),
+            Direction::DownRight => (1, -1),
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Direction::Up => "U",
+            Direction::Down => "D",
+            Direction::Left => "L",
+            Direction::Right => "R",
+            Direction::UpLeft => "UL",
+            Direction::UpRight => "UR",
+            Direction::DownLeft => "DL",
+            Direction::DownRight => "DR",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl<'a> From<&'a str> for Direction {
+    fn from(value: &'a str) -> Self {
+        match value {
+            "U" => Self::Up,
+            "D" => Self::Down,
+            "R" => Self::Right,
+            "L" => Self::Left,
+            "UL" => Self::UpLeft,
+            "UR" => Self::UpRight,
+            "DL" => Self::DownLeft,
+            "DR" => Self::DownRight,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Command {
+    pub direction: Direction,
+    pub steps: i64,
+}
+
+impl<'a> From<&'a str> for Command {
+    fn from(value: &'a str) -> Self {
+        let (dir, steps) = value.split_once(' ').unwrap();
+
+        Self {
+            direction: Direction::from(dir),
+            steps: steps.parse().unwrap(),
+        }
+    }
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.direction, self.steps)
+    }
+}
+
+pub fn parse_commands(data: &str) -> Vec<Command> {
+    data.lines()
+        .filter(|line| !line.is_empty())
+        .map(From::from)
+        .collect()
+}
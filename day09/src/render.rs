@@ -0,0 +1,307 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::io::{self, Write};
+
+use gif::{Encoder, EncodingError, Repeat};
+
+use crate::model::{Command, Point};
+use crate::rope::Rope;
+
+/// When the replay draws a frame
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Granularity {
+    Step,
+    Command,
+}
+
+/// Smallest rectangle containing a set of points
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Bounds {
+    pub min_x: i32,
+    pub max_x: i32,
+    pub min_y: i32,
+    pub max_y: i32,
+}
+
+impl Bounds {
+    pub fn from_points<'p>(points: impl IntoIterator<Item = &'p Point>) -> Self {
+        points.into_iter().fold(
+            Bounds {
+                min_x: 0,
+                max_x: 0,
+                min_y: 0,
+                max_y: 0,
+            },
+            |bounds, point| Bounds {
+                min_x: bounds.min_x.min(point.x),
+                max_x: bounds.max_x.max(point.x),
+                min_y: bounds.min_y.min(point.y),
+                max_y: bounds.max_y.max(point.y),
+            },
+        )
+    }
+
+    /// Bounds of every position visited by the knots of the rope, and of the start
+    pub fn from_rope(rope: &Rope) -> Self {
+        Self::from_points((0..rope.knots().len()).flat_map(|index| rope.visited(index)))
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max_x - self.min_x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max_y - self.min_y + 1) as usize
+    }
+
+    /// Line and column of a point inside the bounds, the top line having the greatest `y`
+    fn cell(&self, point: &Point) -> (usize, usize) {
+        (
+            (self.max_y - point.y) as usize,
+            (point.x - self.min_x) as usize,
+        )
+    }
+
+    fn empty(&self) -> Vec<Vec<u8>> {
+        vec![vec![b'.'; self.width()]; self.height()]
+    }
+}
+
+/// Character of the knot at `index` in the puzzle diagrams: `H` for the head, `T` for the tail of
+/// a two knots rope and the index for the others. Knots past the ninth are drawn as `T` for the
+/// tail and `*` otherwise.
+pub fn knot_name(index: usize, knot_count: usize) -> char {
+    match index {
+        0 => 'H',
+        1 if knot_count == 2 => 'T',
+        1..=9 => char::from_digit(index as u32, 10).unwrap(),
+        _ if index == knot_count - 1 => 'T',
+        _ => '*',
+    }
+}
+
+/// Draw the knots of the rope, the start being drawn as `s`. A knot covers the knots after it and
+/// the start, like in the puzzle.
+pub fn draw_rope(rope: &Rope, bounds: &Bounds) -> Vec<Vec<u8>> {
+    let mut cells = bounds.empty();
+
+    let (line, column) = bounds.cell(&Point::new());
+    cells[line][column] = b's';
+    for (index, knot) in rope.knots().iter().enumerate().rev() {
+        let (line, column) = bounds.cell(knot);
+        // Knot names are ASCII
+        cells[line][column] = knot_name(index, rope.knots().len()) as u8;
+    }
+
+    cells
+}
+
+/// Draw the visited positions as `#`, the start being drawn as `s`
+pub fn draw_visited(visited: &HashSet<Point>, bounds: &Bounds) -> Vec<Vec<u8>> {
+    let mut cells = bounds.empty();
+
+    for point in visited {
+        let (line, column) = bounds.cell(point);
+        cells[line][column] = b'#';
+    }
+    let (line, column) = bounds.cell(&Point::new());
+    cells[line][column] = b's';
+
+    cells
+}
+
+/// A diagram of the replay, with the title of the puzzle for the first frame of a command. Every
+/// cell is an ASCII character.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Frame {
+    pub title: Option<String>,
+    pub cells: Vec<Vec<u8>>,
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(title) = &self.title {
+            writeln!(f, "== {} ==", title)?;
+            writeln!(f)?;
+        }
+        for line in &self.cells {
+            writeln!(f, "{}", String::from_utf8_lossy(line))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Replay the commands on a new rope and draw it on the bounding box of the whole motion, after
+/// every step or every command. The last frame is the map of the positions visited by the tail.
+/// The frames are drawn one at a time, so that long command lists can be streamed.
+pub fn replay(
+    commands: &[Command],
+    knot_count: usize,
+    follow_distance: i32,
+    granularity: Granularity,
+) -> Replay<'_> {
+    // Run the whole motion first to know the size of the diagrams
+    let mut rope = Rope::new(knot_count, follow_distance);
+    rope.run(commands);
+    let bounds = Bounds::from_rope(&rope);
+
+    Replay {
+        commands,
+        rope: Rope::new(knot_count, follow_distance),
+        bounds,
+        granularity,
+        started: false,
+        command: 0,
+        step: 0,
+        finished: false,
+    }
+}
+
+/// Iterator over the frames of a replay, see `replay`
+pub struct Replay<'c> {
+    commands: &'c [Command],
+    rope: Rope,
+    bounds: Bounds,
+    granularity: Granularity,
+    started: bool,
+    /// Index of the command being replayed and number of its steps already drawn
+    command: usize,
+    step: i64,
+    finished: bool,
+}
+
+impl Replay<'_> {
+    pub fn bounds(&self) -> &Bounds {
+        &self.bounds
+    }
+
+    fn frame(&self, title: Option<String>) -> Frame {
+        Frame {
+            title,
+            cells: draw_rope(&self.rope, &self.bounds),
+        }
+    }
+}
+
+impl Iterator for Replay<'_> {
+    type Item = Frame;
+
+    fn next(&mut self) -> Option<Frame> {
+        if !self.started {
+            self.started = true;
+            return Some(self.frame(Some("Initial State".to_string())));
+        }
+
+        while let Some(command) = self.commands.get(self.command) {
+            match self.granularity {
+                Granularity::Step if self.step < command.steps => {
+                    self.rope.step(command.direction);
+                    self.step += 1;
+                    return Some(self.frame((self.step == 1).then(|| command.to_string())));
+                }
+                Granularity::Step => {
+                    self.command += 1;
+                    self.step = 0;
+                }
+                Granularity::Command => {
+                    self.rope.apply(command);
+                    self.command += 1;
+                    return Some(self.frame(Some(command.to_string())));
+                }
+            }
+        }
+
+        if self.finished {
+            return None;
+        }
+        self.finished = true;
+        let tail = self.rope.knots().len() - 1;
+        Some(Frame {
+            title: Some("Visited".to_string()),
+            cells: draw_visited(self.rope.visited(tail), &self.bounds),
+        })
+    }
+}
+
+/// Write the frames as text, separated by empty lines like in the puzzle
+pub fn write_text(
+    frames: impl IntoIterator<Item = Frame>,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    for (index, frame) in frames.into_iter().enumerate() {
+        if index > 0 {
+            writeln!(writer)?;
+        }
+        write!(writer, "{}", frame)?;
+    }
+
+    Ok(())
+}
+
+/// Write the frames as text in a string, see `write_text`
+pub fn to_text(frames: &[Frame]) -> String {
+    let mut text = vec![];
+    write_text(frames.iter().cloned(), &mut text).unwrap();
+
+    String::from_utf8(text).unwrap()
+}
+
+/// Colors of the GIF: background, start, visited, head, tail and other knots
+const PALETTE: [u8; 18] = [
+    16, 16, 16, 96, 96, 96, 40, 90, 200, 220, 40, 40, 40, 200, 60, 230, 200, 40,
+];
+
+fn palette_index(cell: u8) -> u8 {
+    match cell {
+        b'.' => 0,
+        b's' => 1,
+        b'#' => 2,
+        b'H' => 3,
+        b'T' => 4,
+        _ => 5,
+    }
+}
+
+/// Write the frames as an animated GIF with `scale` pixels per cell, waiting `delay` hundredths
+/// of a second between frames. Every frame is encoded as soon as it is drawn.
+pub fn write_gif(
+    frames: impl IntoIterator<Item = Frame>,
+    writer: impl Write,
+    scale: usize,
+    delay: u16,
+) -> Result<(), EncodingError> {
+    let scale = scale.max(1);
+    let mut frames = frames.into_iter().peekable();
+    let (height, width) = frames
+        .peek()
+        .map_or((0, 0), |frame| (frame.cells.len(), frame.cells[0].len()));
+    let size = |cells: usize| {
+        u16::try_from(cells * scale).map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "the frames are too big for a GIF, use a smaller scale",
+            )
+        })
+    };
+    let (pixel_width, pixel_height) = (size(width)?, size(height)?);
+
+    let mut encoder = Encoder::new(writer, pixel_width, pixel_height, &PALETTE)?;
+    encoder.set_repeat(Repeat::Infinite)?;
+
+    for frame in frames {
+        let cells = &frame.cells;
+        let pixels: Vec<u8> = (0..height * scale)
+            .flat_map(|y| {
+                (0..width * scale).map(move |x| palette_index(cells[y / scale][x / scale]))
+            })
+            .collect();
+
+        let mut gif_frame =
+            gif::Frame::from_indexed_pixels(pixel_width, pixel_height, pixels, None);
+        gif_frame.delay = delay;
+        encoder.write_frame(&gif_frame)?;
+    }
+
+    Ok(())
+}
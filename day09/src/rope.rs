@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+
+use crate::model::{Command, Direction, Point};
+
+/// Distance at which a knot starts following the previous one in the puzzle
+pub const DEFAULT_FOLLOW_DISTANCE: i32 = 2;
+
+/// A rope made of knots, the first one being the head. Every knot follows the previous one
+/// once they are `follow_distance` apart.
+#[derive(Debug, Clone)]
+pub struct Rope {
+    knots: Vec<Point>,
+    follow_distance: i32,
+    visited: Vec<HashSet<Point>>,
+}
+
+impl Rope {
+    pub fn new(knot_count: usize, follow_distance: i32) -> Self {
+        assert!(knot_count > 0, "A rope needs at least one knot");
+        assert!(follow_distance > 0, "The follow distance must be positive");
+
+        Self {
+            knots: vec![Point::new(); knot_count],
+            follow_distance,
+            visited: vec![HashSet::from([Point::new()]); knot_count],
+        }
+    }
+
+    pub fn knots(&self) -> &[Point] {
+        &self.knots
+    }
+
+    pub fn head(&self) -> &Point {
+        &self.knots[0]
+    }
+
+    pub fn tail(&self) -> &Point {
+        self.knots.last().unwrap()
+    }
+
+    /// Positions visited by the knot at `index`, the head being at index 0
+    pub fn visited(&self, index: usize) -> &HashSet<Point> {
+        &self.visited[index]
+    }
+
+    /// Number of positions visited by every knot
+    pub fn visit_counts(&self) -> Vec<usize> {
+        self.visited.iter().map(|visited| visited.len()).collect()
+    }
+
+    /// Move the head by one step and let the other knots follow
+    pub fn step(&mut self, direction: Direction) {
+        let (dx, dy) = direction.offset();
+        self.knots[0].x += dx;
+        self.knots[0].y += dy;
+        self.visited[0].insert(self.knots[0]);
+
+        for index in 1..self.knots.len() {
+            let leader = self.knots[index - 1];
+            let knot = &mut self.knots[index];
+            if knot.distance(&leader) < self.follow_distance {
+                // The next knots cannot move either
+                break;
+            }
+
+            knot.x += (leader.x - knot.x).signum();
+            knot.y += (leader.y - knot.y).signum();
+            self.visited[index].insert(*knot);
+        }
+    }
+
+    pub fn apply(&mut self, command: &Command) {
+        for _ in 0..command.steps {
+            self.step(command.direction);
+        }
+    }
+
+    pub fn run(&mut self, commands: &[Command]) {
+        for command in commands {
+            self.apply(command);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use day09::model::parse_commands;
+use day09::render::{knot_name, replay, to_text, write_gif, Granularity};
+use day09::rope::DEFAULT_FOLLOW_DISTANCE;
+
+static INPUT_EXAMPLE: &str = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2
+";
+
+#[test]
+fn test_knot_name() {
+    assert_eq!(knot_name(0, 2), 'H');
+    assert_eq!(knot_name(1, 2), 'T');
+    assert_eq!(knot_name(1, 10), '1');
+    assert_eq!(knot_name(9, 10), '9');
+    assert_eq!(knot_name(10, 12), '*');
+    assert_eq!(knot_name(11, 12), 'T');
+}
+
+#[test]
+fn test_replay_steps() {
+    let frames = replay(
+        &parse_commands(INPUT_EXAMPLE),
+        2,
+        DEFAULT_FOLLOW_DISTANCE,
+        Granularity::Step,
+    )
+    .collect::<Vec<_>>();
+
+    // Initial state, one frame per step and the visited map
+    assert_eq!(frames.len(), 1 + 24 + 1);
+    assert_eq!(
+        to_text(&frames[..3]),
+        "== Initial State ==
+
+......
+......
+......
+......
+H.....
+
+== R 4 ==
+
+......
+......
+......
+......
+TH....
+
+......
+......
+......
+......
+sTH...
+"
+    );
+    assert_eq!(
+        frames.last().unwrap().to_string(),
+        "== Visited ==
+
+..##..
+...##.
+.####.
+....#.
+s###..
+"
+    );
+}
+
+#[test]
+fn test_replay_commands() {
+    let frames = replay(
+        &parse_commands(INPUT_EXAMPLE),
+        10,
+        DEFAULT_FOLLOW_DISTANCE,
+        Granularity::Command,
+    )
+    .collect::<Vec<_>>();
+
+    assert_eq!(frames.len(), 1 + 8 + 1);
+    assert_eq!(
+        frames[2].to_string(),
+        "== U 4 ==
+
+....H.
+....1.
+..432.
+.5....
+6.....
+"
+    );
+}
+
+#[test]
+fn test_write_gif() {
+    let frames = replay(
+        &parse_commands(INPUT_EXAMPLE),
+        10,
+        DEFAULT_FOLLOW_DISTANCE,
+        Granularity::Command,
+    )
+    .collect::<Vec<_>>();
+
+    let mut output = vec![];
+    write_gif(frames, &mut output, 2, 10).unwrap();
+
+    assert_eq!(&output[..6], b"GIF89a");
+    // Logical screen of 6x5 cells of 2 pixels
+    assert_eq!(&output[6..10], &[12, 0, 10, 0]);
+}
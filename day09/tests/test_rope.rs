@@ -0,0 +1,96 @@
+use day09::model::{parse_commands, Command, Direction, Point};
+use day09::rope::{Rope, DEFAULT_FOLLOW_DISTANCE};
+
+static INPUT_EXAMPLE: &str = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2
+";
+
+static INPUT_EXAMPLE_2: &str = "R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20
+";
+
+#[test]
+fn test_parse_diagonal_commands() {
+    assert_eq!(
+        parse_commands("UL 3\nDR 12\n"),
+        vec![
+            Command {
+                direction: Direction::UpLeft,
+                steps: 3
+            },
+            Command {
+                direction: Direction::DownRight,
+                steps: 12
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_visit_counts() {
+    let mut rope = Rope::new(10, DEFAULT_FOLLOW_DISTANCE);
+    rope.run(&parse_commands(INPUT_EXAMPLE));
+    let counts = rope.visit_counts();
+    assert_eq!(counts[1], 13);
+    assert_eq!(counts[9], 1);
+
+    let mut rope = Rope::new(10, DEFAULT_FOLLOW_DISTANCE);
+    rope.run(&parse_commands(INPUT_EXAMPLE_2));
+    assert_eq!(rope.visit_counts()[9], 36);
+    assert_eq!(*rope.tail(), Point { x: -11, y: 6 });
+}
+
+#[test]
+fn test_two_knots_rope() {
+    let mut rope = Rope::new(2, DEFAULT_FOLLOW_DISTANCE);
+    rope.run(&parse_commands(INPUT_EXAMPLE));
+
+    assert_eq!(rope.visit_counts()[1], 13);
+    assert_eq!(*rope.head(), Point { x: 2, y: 2 });
+    assert_eq!(*rope.tail(), Point { x: 1, y: 2 });
+}
+
+#[test]
+fn test_diagonal_moves() {
+    let mut rope = Rope::new(3, DEFAULT_FOLLOW_DISTANCE);
+    rope.run(&parse_commands("UR 3\n"));
+
+    assert_eq!(
+        rope.knots(),
+        &[
+            Point { x: 3, y: 3 },
+            Point { x: 2, y: 2 },
+            Point { x: 1, y: 1 }
+        ]
+    );
+    assert_eq!(rope.visit_counts(), vec![4, 3, 2]);
+}
+
+#[test]
+fn test_follow_distance() {
+    let mut rope = Rope::new(2, 3);
+    rope.run(&parse_commands("R 2\n"));
+    assert_eq!(*rope.tail(), Point::new());
+
+    rope.run(&parse_commands("R 1\nU 1\n"));
+    assert_eq!(*rope.tail(), Point { x: 1, y: 0 });
+
+    rope.run(&parse_commands("U 1\n"));
+    assert_eq!(*rope.tail(), Point { x: 1, y: 0 });
+
+    rope.run(&parse_commands("U 1\n"));
+    assert_eq!(*rope.tail(), Point { x: 2, y: 1 });
+    assert_eq!(rope.visit_counts(), vec![7, 3]);
+}
@@ -0,0 +1,256 @@
+use crate::instruction::Instruction;
+use crate::watch::Expression;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Register {
+    X,
+    Y,
+    Pc,
+}
+
+/// Registers of the CPU
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct State {
+    pub x: i64,
+    pub y: i64,
+    /// Index of the instruction being executed
+    pub pc: usize,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self { x: 1, y: 0, pc: 0 }
+    }
+}
+
+impl State {
+    pub fn get(&self, register: Register) -> i64 {
+        match register {
+            Register::X => self.x,
+            Register::Y => self.y,
+            Register::Pc => self.pc as i64,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Breakpoint {
+    /// Stop before the given cycle
+    Cycle(usize),
+    /// Stop before the first cycle during which the register has the value, after it had
+    /// another one
+    Register(Register, i64),
+}
+
+/// Why `Cpu::run` returned
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Stop {
+    /// The program counter went past the end of the program
+    Halted,
+    /// A breakpoint was reached, `cycle` is the next cycle to run
+    Breakpoint { index: usize, cycle: usize },
+    /// The cycle limit was reached before the program halted, it may loop forever
+    CycleLimit { cycles: usize },
+}
+
+/// An executed instruction
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TraceEntry {
+    pub first_cycle: usize,
+    pub last_cycle: usize,
+    pub instruction: String,
+    pub before: State,
+    pub after: State,
+}
+
+/// A value of a watch expression, recorded every time it changes
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct WatchEvent {
+    pub cycle: usize,
+    pub name: String,
+    pub value: i64,
+}
+
+struct Watch {
+    name: String,
+    expression: Expression,
+    last: Option<i64>,
+}
+
+/// Cycle accurate CPU. Iterating over it runs the program one cycle at a time and yields the
+/// number of every cycle and the value of `X` during it.
+pub struct Cpu<'p> {
+    program: &'p [Box<dyn Instruction>],
+    state: State,
+    /// Number of cycles completed
+    cycle: usize,
+    /// Number of cycles already spent in the current instruction
+    elapsed: usize,
+    /// State during the last completed cycle
+    previous: Option<State>,
+    breakpoints: Vec<Breakpoint>,
+    stopped_at: Option<usize>,
+    watches: Vec<Watch>,
+    watch_events: Vec<WatchEvent>,
+    trace: Option<Vec<TraceEntry>>,
+    cycle_limit: Option<usize>,
+}
+
+impl<'p> Cpu<'p> {
+    pub fn new(program: &'p [Box<dyn Instruction>]) -> Self {
+        Self {
+            program,
+            state: State::default(),
+            cycle: 0,
+            elapsed: 0,
+            previous: None,
+            breakpoints: vec![],
+            stopped_at: None,
+            watches: vec![],
+            watch_events: vec![],
+            trace: None,
+            cycle_limit: None,
+        }
+    }
+
+    /// Current registers, which are the ones used during the next cycle
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Number of cycles completed
+    pub fn cycles_completed(&self) -> usize {
+        self.cycle
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.state.pc >= self.program.len()
+    }
+
+    /// Add a breakpoint and return its index
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> usize {
+        self.breakpoints.push(breakpoint);
+        self.breakpoints.len() - 1
+    }
+
+    /// Evaluate the expression during every cycle and record its value when it changes
+    pub fn add_watch(&mut self, name: &str, expression: Expression) {
+        self.watches.push(Watch {
+            name: name.to_string(),
+            expression,
+            last: None,
+        });
+    }
+
+    pub fn watch_events(&self) -> &[WatchEvent] {
+        &self.watch_events
+    }
+
+    /// Make `run` stop once this number of cycles is completed, as the jumps let a program loop
+    /// forever
+    pub fn set_cycle_limit(&mut self, cycle_limit: usize) {
+        self.cycle_limit = Some(cycle_limit);
+    }
+
+    /// Record every executed instruction from now on
+    pub fn enable_trace(&mut self) {
+        self.trace.get_or_insert_with(Vec::new);
+    }
+
+    pub fn trace(&self) -> &[TraceEntry] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// Run one cycle and return its number and the value of `X` during it, `None` if the CPU is
+    /// halted
+    pub fn tick(&mut self) -> Option<(usize, i64)> {
+        if self.is_halted() {
+            return None;
+        }
+
+        self.cycle += 1;
+        let during = self.state;
+        for watch in &mut self.watches {
+            let value = watch.expression.evaluate(self.cycle, &during);
+            if watch.last != Some(value) {
+                watch.last = Some(value);
+                self.watch_events.push(WatchEvent {
+                    cycle: self.cycle,
+                    name: watch.name.clone(),
+                    value,
+                });
+            }
+        }
+
+        let instruction = &self.program[self.state.pc];
+        let cycles = instruction.cycles().max(1);
+        self.elapsed += 1;
+        if self.elapsed == cycles {
+            self.elapsed = 0;
+            self.state.pc += 1;
+            instruction.execute(&mut self.state);
+
+            if let Some(trace) = &mut self.trace {
+                trace.push(TraceEntry {
+                    first_cycle: self.cycle + 1 - cycles,
+                    last_cycle: self.cycle,
+                    instruction: instruction.to_string(),
+                    before: during,
+                    after: self.state,
+                });
+            }
+        }
+        self.previous = Some(during);
+
+        Some((self.cycle, during.x))
+    }
+
+    fn reached_breakpoint(&self) -> Option<usize> {
+        let next_cycle = self.cycle + 1;
+        self.breakpoints
+            .iter()
+            .position(|breakpoint| match breakpoint {
+                Breakpoint::Cycle(cycle) => *cycle == next_cycle,
+                Breakpoint::Register(register, value) => {
+                    self.state.get(*register) == *value
+                        && self
+                            .previous
+                            .is_none_or(|previous| previous.get(*register) != *value)
+                }
+            })
+    }
+
+    /// Run until the program halts, a breakpoint is reached or the cycle limit is reached.
+    /// Running again resumes the program from the breakpoint.
+    pub fn run(&mut self) -> Stop {
+        loop {
+            if self.is_halted() {
+                return Stop::Halted;
+            }
+            if self.cycle_limit.is_some_and(|limit| self.cycle >= limit) {
+                return Stop::CycleLimit { cycles: self.cycle };
+            }
+
+            let next_cycle = self.cycle + 1;
+            if self.stopped_at != Some(next_cycle) {
+                if let Some(index) = self.reached_breakpoint() {
+                    self.stopped_at = Some(next_cycle);
+                    return Stop::Breakpoint {
+                        index,
+                        cycle: next_cycle,
+                    };
+                }
+            }
+
+            self.tick();
+        }
+    }
+}
+
+impl<'p> Iterator for Cpu<'p> {
+    type Item = (usize, i64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.tick()
+    }
+}
@@ -0,0 +1,138 @@
+use std::fmt::{Display, Formatter};
+
+use crate::cpu::Cpu;
+
+pub const SCREEN_WIDTH: usize = 40;
+pub const SCREEN_HEIGHT: usize = 6;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ScreenError {
+    /// A pixel is neither `#` nor `.`
+    InvalidPixel {
+        line: usize,
+        column: usize,
+        pixel: char,
+    },
+    /// A line does not have the width of the first one
+    RaggedLine {
+        line: usize,
+        width: usize,
+        expected: usize,
+    },
+}
+
+impl Display for ScreenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScreenError::InvalidPixel {
+                line,
+                column,
+                pixel,
+            } => write!(
+                f,
+                "line {}, column {}: invalid pixel '{}'",
+                line, column, pixel
+            ),
+            ScreenError::RaggedLine {
+                line,
+                width,
+                expected,
+            } => write!(
+                f,
+                "line {} has {} pixels instead of {}",
+                line, width, expected
+            ),
+        }
+    }
+}
+
+/// Pixels drawn by the CRT, `true` being lit
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Screen {
+    pixels: Vec<Vec<bool>>,
+}
+
+impl Screen {
+    /// Draw one pixel per cycle, lit when the sprite centered on `X` covers it. Pixels drawn
+    /// after the CPU halted stay dark.
+    pub fn draw(cpu: Cpu) -> Self {
+        let mut pixels = vec![vec![false; SCREEN_WIDTH]; SCREEN_HEIGHT];
+
+        for (cycle, x) in cpu.take(SCREEN_WIDTH * SCREEN_HEIGHT) {
+            let line = (cycle - 1) / SCREEN_WIDTH;
+            let column = (cycle - 1) % SCREEN_WIDTH;
+            pixels[line][column] = (x - column as i64).abs() <= 1;
+        }
+
+        Self { pixels }
+    }
+
+    /// Screen of the given pixels, line by line
+    pub fn new(pixels: Vec<Vec<bool>>) -> Self {
+        Self { pixels }
+    }
+
+    /// Parse a screen drawn with `#` and `.`, every line having the same width
+    pub fn parse(data: &str) -> Result<Self, ScreenError> {
+        let mut pixels: Vec<Vec<bool>> = vec![];
+        for (index, line) in data.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+
+            let line_pixels = line
+                .chars()
+                .enumerate()
+                .map(|(column, pixel)| match pixel {
+                    '#' => Ok(true),
+                    '.' => Ok(false),
+                    _ => Err(ScreenError::InvalidPixel {
+                        line: index + 1,
+                        column: column + 1,
+                        pixel,
+                    }),
+                })
+                .collect::<Result<Vec<bool>, _>>()?;
+            if let Some(first) = pixels.first() {
+                if line_pixels.len() != first.len() {
+                    return Err(ScreenError::RaggedLine {
+                        line: index + 1,
+                        width: line_pixels.len(),
+                        expected: first.len(),
+                    });
+                }
+            }
+            pixels.push(line_pixels);
+        }
+
+        Ok(Self { pixels })
+    }
+
+    pub fn width(&self) -> usize {
+        self.pixels.first().map_or(0, |line| line.len())
+    }
+
+    pub fn height(&self) -> usize {
+        self.pixels.len()
+    }
+
+    pub fn is_lit(&self, line: usize, column: usize) -> bool {
+        self.pixels[line][column]
+    }
+}
+
+impl Display for Screen {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for line in &self.pixels {
+            writeln!(
+                f,
+                "{}",
+                line.iter()
+                    .map(|lit| if *lit { '#' } else { '.' })
+                    .collect::<String>()
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+
+use crate::cpu::State;
+
+/// An instruction of the CPU. It takes `cycles()` cycles, and its effect is applied to the state
+/// at the end of its last cycle.
+pub trait Instruction: Debug + Display {
+    /// Number of cycles taken by the instruction, at least 1
+    fn cycles(&self) -> usize;
+
+    /// Apply the effect of the instruction. When it is called, `pc` already points to the next
+    /// instruction.
+    fn execute(&self, state: &mut State);
+}
+
+pub type Program = Vec<Box<dyn Instruction>>;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Noop;
+
+impl Instruction for Noop {
+    fn cycles(&self) -> usize {
+        1
+    }
+
+    fn execute(&self, _state: &mut State) {}
+}
+
+impl Display for Noop {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "noop")
+    }
+}
+
+/// Add a value to `X` in two cycles
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Addx(pub i64);
+
+impl Instruction for Addx {
+    fn cycles(&self) -> usize {
+        2
+    }
+
+    fn execute(&self, state: &mut State) {
+        state.x += self.0;
+    }
+}
+
+impl Display for Addx {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "addx {}", self.0)
+    }
+}
+
+/// Add a value to `Y` in three cycles
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Addy(pub i64);
+
+impl Instruction for Addy {
+    fn cycles(&self) -> usize {
+        3
+    }
+
+    fn execute(&self, state: &mut State) {
+        state.y += self.0;
+    }
+}
+
+impl Display for Addy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "addy {}", self.0)
+    }
+}
+
+/// Jump to the instruction at the given offset from this one, in one cycle. Jumping before the
+/// first instruction halts the CPU.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Jmp(pub i64);
+
+impl Instruction for Jmp {
+    fn cycles(&self) -> usize {
+        1
+    }
+
+    fn execute(&self, state: &mut State) {
+        state.pc = (state.pc as i64 - 1 + self.0)
+            .try_into()
+            .unwrap_or(usize::MAX);
+    }
+}
+
+impl Display for Jmp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "jmp {}", self.0)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ParseError {
+    UnknownInstruction { line: usize, name: String },
+    InvalidArguments { line: usize, text: String },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::UnknownInstruction { line, name } => {
+                write!(f, "line {}: unknown instruction {}", line, name)
+            }
+            ParseError::InvalidArguments { line, text } => {
+                write!(f, "line {}: invalid arguments in {}", line, text)
+            }
+        }
+    }
+}
+
+/// Build an instruction from its arguments, `None` if they are invalid
+pub type InstructionParser = fn(&[&str]) -> Option<Box<dyn Instruction>>;
+
+fn parse_value(arguments: &[&str]) -> Option<i64> {
+    match arguments {
+        [value] => value.parse().ok(),
+        _ => None,
+    }
+}
+
+/// The instructions understood by the parser, by name
+pub struct InstructionSet {
+    parsers: HashMap<String, InstructionParser>,
+}
+
+impl Default for InstructionSet {
+    /// The instructions of the puzzle: `noop` and `addx`
+    fn default() -> Self {
+        let mut set = Self {
+            parsers: HashMap::new(),
+        };
+        set.register("noop", |arguments| {
+            arguments
+                .is_empty()
+                .then(|| Box::new(Noop) as Box<dyn Instruction>)
+        });
+        set.register("addx", |arguments| {
+            parse_value(arguments).map(|value| Box::new(Addx(value)) as Box<dyn Instruction>)
+        });
+
+        set
+    }
+}
+
+impl InstructionSet {
+    /// `noop` and `addx` from the puzzle, plus the `addy` and `jmp` extensions
+    pub fn extended() -> Self {
+        let mut set = Self::default();
+        set.register("addy", |arguments| {
+            parse_value(arguments).map(|value| Box::new(Addy(value)) as Box<dyn Instruction>)
+        });
+        set.register("jmp", |arguments| {
+            parse_value(arguments).map(|value| Box::new(Jmp(value)) as Box<dyn Instruction>)
+        });
+
+        set
+    }
+
+    /// Add an instruction, replacing any instruction with the same name
+    pub fn register(&mut self, name: &str, parser: InstructionParser) {
+        self.parsers.insert(name.to_string(), parser);
+    }
+
+    /// Parse one instruction per line, empty lines are ignored
+    pub fn parse(&self, data: &str) -> Result<Program, ParseError> {
+        data.lines()
+            .enumerate()
+            .filter(|(_, text)| !text.trim().is_empty())
+            .map(|(index, text)| {
+                let mut words = text.split_whitespace();
+                let name = words.next().unwrap();
+                let arguments: Vec<&str> = words.collect();
+
+                let parser =
+                    self.parsers
+                        .get(name)
+                        .ok_or_else(|| ParseError::UnknownInstruction {
+                            line: index + 1,
+                            name: name.to_string(),
+                        })?;
+                parser(&arguments).ok_or_else(|| ParseError::InvalidArguments {
+                    line: index + 1,
+                    text: text.to_string(),
+                })
+            })
+            .collect()
+    }
+}
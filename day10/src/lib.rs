@@ -0,0 +1,6 @@
+pub mod cpu;
+pub mod crt;
+pub mod instruction;
+pub mod ocr;
+pub mod synthesizer;
+pub mod watch;
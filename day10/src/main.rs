@@ -1,119 +1,184 @@
-use std::collections::HashSet;
-use std::{env, fs};
+use std::fs;
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use day10::cpu::{Breakpoint, Cpu, Register, Stop};
+use day10::crt::Screen;
+use day10::instruction::{InstructionSet, Program};
+use day10::ocr::recognize;
+use day10::synthesizer::{synthesize, text_screen};
+use day10::watch::Expression;
+
+#[derive(Parser, Debug, Eq, PartialEq, Clone)]
+struct Cli {
+    /// Enable verbose display
+    #[arg(short, long, default_value_t = false)]
+    verbose: bool,
+
+    /// Also accept the addy and jmp instructions
+    #[arg(short, long, default_value_t = false)]
+    extended: bool,
+
+    /// Stop before this cycle and display the registers
+    #[arg(short, long)]
+    break_cycle: Vec<usize>,
+
+    /// Stop when X takes this value and display the registers
+    #[arg(long, allow_negative_numbers = true)]
+    break_x: Vec<i64>,
+
+    /// Display every change of an expression over cycle, x, y and pc, like "cycle * x"
+    #[arg(short, long, value_parser = parse_watch)]
+    watch: Vec<(String, Expression)>,
+
+    /// Display every executed instruction
+    #[arg(short, long, default_value_t = false)]
+    trace: bool,
+
+    /// Stop debugging after this number of cycles, as jumps may loop forever
+    #[arg(long, default_value_t = 1_000_000)]
+    max_cycles: usize,
+
+    /// Print a shortest program writing this text on the CRT instead of solving the puzzle
+    #[arg(long)]
+    draw: Option<String>,
+
+    /// Print a shortest program drawing the screen of this file, made of # and ., instead of
+    /// solving the puzzle
+    #[arg(long)]
+    draw_pattern: Option<PathBuf>,
+
+    /// File to parse
+    #[arg(default_value = "input")]
+    path: PathBuf,
+}
 
-#[derive(Debug, Eq, PartialEq, Clone)]
-enum Instruction {
-    Addx(i32),
-    Noop,
+fn parse_watch(value: &str) -> Result<(String, Expression), String> {
+    Ok((value.to_string(), Expression::parse(value)?))
 }
 
-impl<'a> From<&'a str> for Instruction {
-    fn from(value: &'a str) -> Self {
-        if value == "noop" {
-            return Self::Noop;
-        }
+fn solve_part_one(program: &Program) {
+    // The program may loop forever, the signal is only measured up to cycle 220
+    let signal_strength: i64 = Cpu::new(program)
+        .take_while(|(cycle, _)| *cycle <= 220)
+        .filter(|(cycle, _)| [20, 60, 100, 140, 180, 220].contains(cycle))
+        .map(|(cycle, x)| cycle as i64 * x)
+        .sum();
 
-        if value.starts_with("addx") {
-            let (_, val) = value.split_once(" ").unwrap();
-            return Self::Addx(val.parse().unwrap());
-        }
+    println!("Part one solution: {:#?}", signal_strength);
+}
 
-        unreachable!()
+fn solve_part_two(program: &Program, verbose: bool) {
+    let screen = Screen::draw(Cpu::new(program));
+    if verbose {
+        print!("{}", screen);
     }
-}
 
-fn solve_part_one(instructions: &[Instruction]) {
-    let mut register: i32 = 1;
-    let mut cycle: i32 = 1;
-    let mut signal_strength: i32 = 0;
-    let target_cycles: HashSet<i32> = [20, 60, 100, 140, 180, 220].into_iter().collect();
-
-    for instruction in instructions {
-        match instruction {
-            Instruction::Addx(val) => {
-                // If the addx operation will happen during the target cycle
-                if target_cycles.contains(&cycle) {
-                    signal_strength += cycle * register;
-                }
-                if target_cycles.contains(&(cycle + 1)) {
-                    signal_strength += (cycle + 1) * register;
-                }
+    match recognize(&screen) {
+        Ok(letters) => println!("Part two solution: {:#?}", letters),
+        Err(err) => println!("Part two solution cannot be read, {}", err),
+    }
+}
 
-                register += val;
-                cycle += 2;
+fn debug(program: &Program, args: &Cli) {
+    let mut cpu = Cpu::new(program);
+    for cycle in &args.break_cycle {
+        cpu.add_breakpoint(Breakpoint::Cycle(*cycle));
+    }
+    for value in &args.break_x {
+        cpu.add_breakpoint(Breakpoint::Register(Register::X, *value));
+    }
+    for (name, expression) in &args.watch {
+        cpu.add_watch(name, expression.clone());
+    }
+    if args.trace {
+        cpu.enable_trace();
+    }
+    cpu.set_cycle_limit(args.max_cycles);
+
+    loop {
+        match cpu.run() {
+            Stop::Breakpoint { cycle, .. } => {
+                let state = cpu.state();
+                println!(
+                    "Breakpoint before cycle {}: x={} y={} pc={}",
+                    cycle, state.x, state.y, state.pc
+                );
             }
-            Instruction::Noop => {
-                // if we are during one of the target cycle
-                if target_cycles.contains(&cycle) {
-                    signal_strength += cycle * register;
-                }
-                cycle += 1;
+            Stop::CycleLimit { cycles } => {
+                println!(
+                    "Stopped after {} cycles, the program may loop forever",
+                    cycles
+                );
+                break;
             }
+            Stop::Halted => break,
         }
     }
 
-    println!("Part one solution: {:#?}", signal_strength);
+    for event in cpu.watch_events() {
+        println!("Cycle {}: {} = {}", event.cycle, event.name, event.value);
+    }
+    for entry in cpu.trace() {
+        println!(
+            "Cycles {}-{}: {:<10} x={} y={} pc={}",
+            entry.first_cycle,
+            entry.last_cycle,
+            entry.instruction,
+            entry.after.x,
+            entry.after.y,
+            entry.after.pc
+        );
+    }
 }
 
-fn solve_part_two(instructions: &[Instruction]) {
-    let mut register: i32 = 1;
-    let mut cycle: i32 = 1;
-    let mut screen: [String; 6] = Default::default();
-
-    for instruction in instructions {
-        match instruction {
-            Instruction::Addx(val) => {
-                // Handle current cycle
-                let screen_pos = (cycle - 1) % 40;
-                let line = ((cycle - 1) / 40) as usize;
-                if (register - screen_pos).abs() <= 1 {
-                    screen[line].push('#')
-                } else {
-                    screen[line].push('.')
-                }
-
-                // handle next cycle
-                let screen_pos = cycle % 40;
-                let line = ((cycle) / 40) as usize;
-                if (register - screen_pos).abs() <= 1 {
-                    screen[line].push('#')
-                } else {
-                    screen[line].push('.')
-                }
-
-                register += val;
-                cycle += 2;
-            }
-            Instruction::Noop => {
-                // Handle current cycle
-                let screen_pos = (cycle - 1) % 40;
-                let line = ((cycle - 1) / 40) as usize;
-                if (register - screen_pos).abs() <= 1 {
-                    screen[line].push('#')
-                } else {
-                    screen[line].push('.')
+fn main() {
+    let args = Cli::parse();
+
+    let target = match (&args.draw, &args.draw_pattern) {
+        (Some(text), _) => Some(text_screen(text).map_err(|err| err.to_string())),
+        (None, Some(pattern)) => Some(
+            fs::read_to_string(pattern)
+                .map_err(|err| format!("unable to read {}: {}", pattern.display(), err))
+                .and_then(|data| Screen::parse(&data).map_err(|err| err.to_string())),
+        ),
+        (None, None) => None,
+    };
+    if let Some(target) = target {
+        match target.and_then(|target| synthesize(&target).map_err(|err| err.to_string())) {
+            Ok(program) => {
+                for instruction in &program {
+                    println!("{}", instruction);
                 }
-
-                cycle += 1
             }
+            Err(err) => println!("Cannot draw the target: {}", err),
         }
+        return;
     }
 
-    println!("Part two solution: {:#?}", screen);
-}
-
-fn main() {
-    let args: Vec<_> = env::args().collect();
-
-    let file_path = if args.len() > 1 {
-        args[1].clone()
+    let data: String = fs::read_to_string(&args.path).unwrap();
+    let instruction_set = if args.extended {
+        InstructionSet::extended()
     } else {
-        "input".to_string()
+        InstructionSet::default()
     };
+    let program = instruction_set.parse(&data).unwrap();
 
-    let data: String = fs::read_to_string(&file_path).unwrap();
-    let instructions: Vec<Instruction> = data.lines().map(From::from).collect();
+    if args.verbose {
+        for instruction in &program {
+            println!("{}", instruction);
+        }
+    }
 
-    solve_part_one(&instructions);
-    solve_part_two(&instructions);
+    solve_part_one(&program);
+    solve_part_two(&program, args.verbose);
+
+    if !args.break_cycle.is_empty()
+        || !args.break_x.is_empty()
+        || !args.watch.is_empty()
+        || args.trace
+    {
+        debug(&program, &args);
+    }
 }
@@ -0,0 +1,102 @@
+use std::fmt::{Display, Formatter};
+
+use crate::crt::Screen;
+
+pub const GLYPH_WIDTH: usize = 4;
+pub const GLYPH_HEIGHT: usize = 6;
+/// Glyphs are separated by an empty column
+const GLYPH_SPACING: usize = GLYPH_WIDTH + 1;
+
+/// The letters of the font used by the puzzles, each glyph being written line by line
+const FONT: [(char, &str); 16] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum OcrError {
+    /// The screen is not made of lines of glyphs
+    InvalidSize { width: usize, height: usize },
+    /// The glyph at `index` is not a letter of the font
+    UnknownGlyph { index: usize, bitmap: String },
+}
+
+impl Display for OcrError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OcrError::InvalidSize { width, height } => write!(
+                f,
+                "a screen of {}x{} pixels cannot be split into {}x{} glyphs",
+                width, height, GLYPH_WIDTH, GLYPH_HEIGHT
+            ),
+            OcrError::UnknownGlyph { index, bitmap } => {
+                writeln!(f, "unknown glyph at position {}:", index)?;
+                write!(f, "{}", bitmap)
+            }
+        }
+    }
+}
+
+/// Pixels of the glyph of a letter, line by line, `None` if the font does not have it
+pub fn glyph(letter: char) -> Option<&'static str> {
+    FONT.iter()
+        .find(|(font_letter, _)| *font_letter == letter)
+        .map(|(_, glyph)| *glyph)
+}
+
+/// Pixels of the glyph at `index`, one line of `#` and `.` per line of the glyph
+fn glyph_bitmap(screen: &Screen, index: usize) -> String {
+    (0..GLYPH_HEIGHT)
+        .map(|line| {
+            (0..GLYPH_WIDTH)
+                .map(|column| {
+                    if screen.is_lit(line, index * GLYPH_SPACING + column) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+                + "\n"
+        })
+        .collect()
+}
+
+/// Read the letters drawn on the screen
+pub fn recognize(screen: &Screen) -> Result<String, OcrError> {
+    let width = screen.width();
+    // The last glyph does not need its empty column
+    if screen.height() != GLYPH_HEIGHT
+        || !(width.is_multiple_of(GLYPH_SPACING) || (width + 1).is_multiple_of(GLYPH_SPACING))
+    {
+        return Err(OcrError::InvalidSize {
+            width,
+            height: screen.height(),
+        });
+    }
+
+    (0..(width + 1) / GLYPH_SPACING)
+        .map(|index| {
+            let bitmap = glyph_bitmap(screen, index);
+            let pixels = bitmap.replace('\n', "");
+            FONT.iter()
+                .find(|(_, glyph)| *glyph == pixels)
+                .map(|(letter, _)| *letter)
+                .ok_or(OcrError::UnknownGlyph { index, bitmap })
+        })
+        .collect()
+}
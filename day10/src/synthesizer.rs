@@ -0,0 +1,201 @@
+use std::fmt::{Display, Formatter};
+
+use crate::cpu::Cpu;
+use crate::crt::{Screen, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::instruction::{Addx, Instruction, Noop, Program};
+use crate::ocr::{glyph, GLYPH_WIDTH};
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SynthesisError {
+    /// The target is not a 40x6 screen
+    InvalidSize { width: usize, height: usize },
+    /// The text does not fit on the screen
+    TextTooLong { length: usize },
+    /// The font does not have this letter
+    UnsupportedLetter(char),
+    /// No program draws the target: every program drawing the pixels before this one draws this
+    /// one wrong
+    Impossible { line: usize, column: usize },
+    /// The simulation of the synthesized program does not draw the target
+    VerificationFailed,
+}
+
+impl Display for SynthesisError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SynthesisError::InvalidSize { width, height } => write!(
+                f,
+                "the target has {}x{} pixels instead of {}x{}",
+                width, height, SCREEN_WIDTH, SCREEN_HEIGHT
+            ),
+            SynthesisError::TextTooLong { length } => {
+                write!(f, "{} letters do not fit on the screen", length)
+            }
+            SynthesisError::UnsupportedLetter(letter) => {
+                write!(f, "the font has no {} letter", letter)
+            }
+            SynthesisError::Impossible { line, column } => write!(
+                f,
+                "no program draws the pixel at line {}, column {} after drawing the ones before it",
+                line, column
+            ),
+            SynthesisError::VerificationFailed => {
+                write!(f, "the synthesized program does not draw the target")
+            }
+        }
+    }
+}
+
+/// Lowest and highest values of `X` considered. Every smaller value lights the same pixels as
+/// the lowest one, which is none, and every greater value lights the same pixels as the highest
+/// one, so the search does not lose any program.
+const X_MIN: i64 = -2;
+const X_MAX: i64 = SCREEN_WIDTH as i64 + 1;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+enum Choice {
+    Halt,
+    Noop,
+    Addx(i64),
+}
+
+/// Screen with the letters drawn like the puzzle does, a space leaving its glyph empty
+pub fn text_screen(text: &str) -> Result<Screen, SynthesisError> {
+    let letters: Vec<char> = text.chars().collect();
+    if letters.len() * (GLYPH_WIDTH + 1) > SCREEN_WIDTH + 1 {
+        return Err(SynthesisError::TextTooLong {
+            length: letters.len(),
+        });
+    }
+
+    let mut pixels = vec![vec![false; SCREEN_WIDTH]; SCREEN_HEIGHT];
+    for (index, letter) in letters.into_iter().enumerate() {
+        if letter == ' ' {
+            continue;
+        }
+        let glyph = glyph(letter).ok_or(SynthesisError::UnsupportedLetter(letter))?;
+        for (position, pixel) in glyph.chars().enumerate() {
+            let line = position / GLYPH_WIDTH;
+            let column = index * (GLYPH_WIDTH + 1) + position % GLYPH_WIDTH;
+            pixels[line][column] = pixel == '#';
+        }
+    }
+
+    Ok(Screen::new(pixels))
+}
+
+/// Find a program with the fewest instructions drawing the target screen, and check it by
+/// running it.
+///
+/// The search is a dynamic programming over the number of cycles already drawn and the value of
+/// `X`: a `noop` draws one pixel, an `addx` draws two pixels and then changes `X`, and the
+/// program may halt as soon as every remaining pixel is dark.
+pub fn synthesize(target: &Screen) -> Result<Program, SynthesisError> {
+    if target.width() != SCREEN_WIDTH || target.height() != SCREEN_HEIGHT {
+        return Err(SynthesisError::InvalidSize {
+            width: target.width(),
+            height: target.height(),
+        });
+    }
+
+    let cycles = SCREEN_WIDTH * SCREEN_HEIGHT;
+    let lit = |cycle: usize| target.is_lit(cycle / SCREEN_WIDTH, cycle % SCREEN_WIDTH);
+    let drawn =
+        |cycle: usize, x: i64| lit(cycle) == ((x - (cycle % SCREEN_WIDTH) as i64).abs() <= 1);
+
+    let values = (X_MAX - X_MIN + 1) as usize;
+    let index = |x: i64| (x - X_MIN) as usize;
+
+    // Whether every pixel from a cycle is dark
+    let mut dark_from = vec![true; cycles + 1];
+    for cycle in (0..cycles).rev() {
+        dark_from[cycle] = dark_from[cycle + 1] && !lit(cycle);
+    }
+
+    // Fewest instructions needed to draw the pixels from a cycle with a value of X
+    let mut best: Vec<Vec<Option<(usize, Choice)>>> = vec![vec![None; values]; cycles + 1];
+    best[cycles] = vec![Some((0, Choice::Halt)); values];
+    for cycle in (0..cycles).rev() {
+        for x in X_MIN..=X_MAX {
+            let mut candidate: Option<(usize, Choice)> = None;
+            let mut consider = |length: usize, choice: Choice| {
+                if candidate.is_none_or(|(best_length, _)| length < best_length) {
+                    candidate = Some((length, choice));
+                }
+            };
+
+            if dark_from[cycle] {
+                consider(0, Choice::Halt);
+            }
+            if drawn(cycle, x) {
+                if let Some((length, _)) = best[cycle + 1][index(x)] {
+                    consider(length + 1, Choice::Noop);
+                }
+                if cycle + 1 < cycles && drawn(cycle + 1, x) {
+                    for next_x in X_MIN..=X_MAX {
+                        if let Some((length, _)) = best[cycle + 2][index(next_x)] {
+                            consider(length + 1, Choice::Addx(next_x - x));
+                        }
+                    }
+                }
+            }
+
+            best[cycle][index(x)] = candidate;
+        }
+    }
+
+    if best[0][index(1)].is_none() {
+        // Find the furthest pixel reached by a program drawing every pixel before it
+        let mut reached = vec![vec![false; values]; cycles + 1];
+        reached[0][index(1)] = true;
+        let mut furthest = 0;
+        for cycle in 0..cycles {
+            for x in X_MIN..=X_MAX {
+                if !reached[cycle][index(x)] {
+                    continue;
+                }
+                furthest = cycle;
+                if drawn(cycle, x) {
+                    reached[cycle + 1][index(x)] = true;
+                    if cycle + 1 < cycles && drawn(cycle + 1, x) {
+                        reached[cycle + 2] = vec![true; values];
+                    }
+                }
+            }
+        }
+
+        return Err(SynthesisError::Impossible {
+            line: furthest / SCREEN_WIDTH,
+            column: furthest % SCREEN_WIDTH,
+        });
+    }
+
+    let mut program: Program = vec![];
+    let (mut cycle, mut x) = (0, 1);
+    loop {
+        let (_, choice) = best[cycle][index(x)].unwrap();
+        match choice {
+            Choice::Halt => break,
+            Choice::Noop => {
+                program.push(Box::new(Noop) as Box<dyn Instruction>);
+                cycle += 1;
+            }
+            Choice::Addx(value) => {
+                program.push(Box::new(Addx(value)));
+                cycle += 2;
+                x += value;
+            }
+        }
+    }
+
+    if Screen::draw(Cpu::new(&program)) != *target {
+        return Err(SynthesisError::VerificationFailed);
+    }
+
+    Ok(program)
+}
+
+/// Find a program with the fewest instructions writing the text on the screen
+pub fn synthesize_text(text: &str) -> Result<Program, SynthesisError> {
+    synthesize(&text_screen(text)?)
+}
@@ -0,0 +1,91 @@
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, i64, multispace0, one_of};
+use nom::combinator::{all_consuming, map, value};
+use nom::multi::many0;
+use nom::sequence::{delimited, pair};
+use nom::IResult;
+
+use crate::cpu::{Register, State};
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+}
+
+/// Expression over the cycle number and the registers, like `cycle * x`
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Expression {
+    Constant(i64),
+    Cycle,
+    Register(Register),
+    Binary(Box<Expression>, Operator, Box<Expression>),
+}
+
+impl Expression {
+    pub fn evaluate(&self, cycle: usize, state: &State) -> i64 {
+        match self {
+            Expression::Constant(value) => *value,
+            Expression::Cycle => cycle as i64,
+            Expression::Register(register) => state.get(*register),
+            Expression::Binary(left, operator, right) => {
+                let left = left.evaluate(cycle, state);
+                let right = right.evaluate(cycle, state);
+                match operator {
+                    Operator::Add => left + right,
+                    Operator::Subtract => left - right,
+                    Operator::Multiply => left * right,
+                }
+            }
+        }
+    }
+
+    pub fn parse(input: &str) -> Result<Expression, String> {
+        all_consuming(parse_expression)(input)
+            .map(|(_, expression)| expression)
+            .map_err(|err| format!("invalid expression {}: {}", input, err))
+    }
+}
+
+fn parse_atom(input: &str) -> IResult<&str, Expression> {
+    delimited(
+        multispace0,
+        alt((
+            map(i64, Expression::Constant),
+            value(Expression::Cycle, tag("cycle")),
+            value(Expression::Register(Register::Pc), tag("pc")),
+            value(Expression::Register(Register::X), tag("x")),
+            value(Expression::Register(Register::Y), tag("y")),
+            delimited(char('('), parse_expression, char(')')),
+        )),
+        multispace0,
+    )(input)
+}
+
+fn fold_binary(first: Expression, rest: Vec<(Operator, Expression)>) -> Expression {
+    rest.into_iter().fold(first, |left, (operator, right)| {
+        Expression::Binary(Box::new(left), operator, Box::new(right))
+    })
+}
+
+fn parse_term(input: &str) -> IResult<&str, Expression> {
+    let (input, first) = parse_atom(input)?;
+    let (input, rest) = many0(pair(value(Operator::Multiply, char('*')), parse_atom))(input)?;
+
+    Ok((input, fold_binary(first, rest)))
+}
+
+fn parse_expression(input: &str) -> IResult<&str, Expression> {
+    let (input, first) = parse_term(input)?;
+    let (input, rest) = many0(pair(
+        map(one_of("+-"), |operator| match operator {
+            '+' => Operator::Add,
+            _ => Operator::Subtract,
+        }),
+        parse_term,
+    ))(input)?;
+
+    Ok((input, fold_binary(first, rest)))
+}
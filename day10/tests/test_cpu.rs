@@ -0,0 +1,164 @@
+use std::fmt::{Display, Formatter};
+
+use day10::cpu::{Breakpoint, Cpu, Register, State, Stop};
+use day10::instruction::{Instruction, InstructionSet, ParseError};
+use day10::watch::Expression;
+
+static INPUT_EXAMPLE: &str = "noop
+addx 3
+addx -5
+";
+
+#[test]
+fn test_cycles() {
+    let program = InstructionSet::default().parse(INPUT_EXAMPLE).unwrap();
+    let mut cpu = Cpu::new(&program);
+
+    assert_eq!(
+        cpu.by_ref().collect::<Vec<(usize, i64)>>(),
+        vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]
+    );
+    assert!(cpu.is_halted());
+    assert_eq!(cpu.state().x, -1);
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(
+        InstructionSet::default()
+            .parse("noop\njmp 2\n")
+            .unwrap_err(),
+        ParseError::UnknownInstruction {
+            line: 2,
+            name: "jmp".to_string()
+        }
+    );
+    assert_eq!(
+        InstructionSet::default().parse("addx\n").unwrap_err(),
+        ParseError::InvalidArguments {
+            line: 1,
+            text: "addx".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_breakpoints() {
+    let program = InstructionSet::default().parse(INPUT_EXAMPLE).unwrap();
+    let mut cpu = Cpu::new(&program);
+    cpu.add_breakpoint(Breakpoint::Cycle(3));
+    cpu.add_breakpoint(Breakpoint::Register(Register::X, 4));
+
+    assert_eq!(cpu.run(), Stop::Breakpoint { index: 0, cycle: 3 });
+    assert_eq!(cpu.cycles_completed(), 2);
+    assert_eq!(cpu.state().x, 1);
+
+    assert_eq!(cpu.run(), Stop::Breakpoint { index: 1, cycle: 4 });
+    assert_eq!(cpu.state().x, 4);
+
+    // X stays at 4 during the next cycle, the breakpoint is not reached again
+    assert_eq!(cpu.run(), Stop::Halted);
+    assert_eq!(cpu.cycles_completed(), 5);
+}
+
+#[test]
+fn test_watch_and_trace() {
+    let program = InstructionSet::default().parse(INPUT_EXAMPLE).unwrap();
+    let mut cpu = Cpu::new(&program);
+    cpu.add_watch("x", Expression::parse("x").unwrap());
+    cpu.enable_trace();
+    cpu.run();
+
+    let events: Vec<(usize, i64)> = cpu
+        .watch_events()
+        .iter()
+        .map(|event| (event.cycle, event.value))
+        .collect();
+    assert_eq!(events, vec![(1, 1), (4, 4)]);
+
+    let trace = cpu.trace();
+    assert_eq!(trace.len(), 3);
+    assert_eq!(trace[1].instruction, "addx 3");
+    assert_eq!((trace[1].first_cycle, trace[1].last_cycle), (2, 3));
+    assert_eq!(trace[1].before.x, 1);
+    assert_eq!(trace[1].after, State { x: 4, y: 0, pc: 2 });
+}
+
+#[test]
+fn test_expression() {
+    let state = State { x: 3, y: -2, pc: 7 };
+
+    assert_eq!(
+        Expression::parse("cycle * x").unwrap().evaluate(20, &state),
+        60
+    );
+    assert_eq!(
+        Expression::parse("2 + x * (y - 1) - pc")
+            .unwrap()
+            .evaluate(1, &state),
+        -14
+    );
+    assert!(Expression::parse("x +").is_err());
+}
+
+#[test]
+fn test_extended_instructions() {
+    let program = InstructionSet::extended()
+        .parse("addy 2\naddx 1\njmp -2\n")
+        .unwrap();
+    let mut cpu = Cpu::new(&program);
+    cpu.add_breakpoint(Breakpoint::Register(Register::Y, 4));
+
+    // addy takes 3 cycles, addx 2 and jmp 1, the loop never ends
+    assert_eq!(
+        cpu.run(),
+        Stop::Breakpoint {
+            index: 0,
+            cycle: 10
+        }
+    );
+    assert_eq!(*cpu.state(), State { x: 2, y: 4, pc: 1 });
+
+    assert_eq!(cpu.by_ref().take(6).last(), Some((15, 3)));
+
+    cpu.set_cycle_limit(100);
+    assert_eq!(cpu.run(), Stop::CycleLimit { cycles: 100 });
+    assert_eq!(cpu.run(), Stop::CycleLimit { cycles: 100 });
+}
+
+/// Double X in four cycles
+#[derive(Debug)]
+struct Dblx;
+
+impl Instruction for Dblx {
+    fn cycles(&self) -> usize {
+        4
+    }
+
+    fn execute(&self, state: &mut State) {
+        state.x *= 2;
+    }
+}
+
+impl Display for Dblx {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "dblx")
+    }
+}
+
+#[test]
+fn test_custom_instruction() {
+    let mut instruction_set = InstructionSet::default();
+    instruction_set.register("dblx", |arguments| {
+        arguments
+            .is_empty()
+            .then(|| Box::new(Dblx) as Box<dyn Instruction>)
+    });
+
+    let program = instruction_set.parse("addx 2\ndblx\nnoop\n").unwrap();
+    let states: Vec<(usize, i64)> = Cpu::new(&program).collect();
+    assert_eq!(
+        states,
+        vec![(1, 1), (2, 1), (3, 3), (4, 3), (5, 3), (6, 3), (7, 6)]
+    );
+}
@@ -0,0 +1,102 @@
+use day10::cpu::Cpu;
+use day10::crt::{Screen, ScreenError};
+use day10::instruction::InstructionSet;
+use day10::ocr::{recognize, OcrError};
+
+static SCREEN: &str = "\
+.##..###...##..####.####..##..#..#...##.
+#..#.#..#.#..#.#....#....#..#.#..#....#.
+#..#.###..#....###..###..#....####....#.
+####.#..#.#....#....#....#.##.#..#....#.
+#..#.#..#.#..#.#....#....#..#.#..#.#..#.
+#..#.###...##..####.#.....###.#..#..##..
+";
+
+static SCREEN_2: &str = "\
+#..#.#.....##..###..###...###.#..#.####.
+#.#..#....#..#.#..#.#..#.#....#..#....#.
+##...#....#..#.#..#.#..#.#....#..#...#..
+#.#..#....#..#.###..###...##..#..#..#...
+#.#..#....#..#.#....#.#.....#.#..#.#....
+#..#.####..##..#....#..#.###...##..####.
+";
+
+#[test]
+fn test_recognize() {
+    assert_eq!(
+        recognize(&Screen::parse(SCREEN).unwrap()),
+        Ok("ABCEFGHJ".to_string())
+    );
+    assert_eq!(
+        recognize(&Screen::parse(SCREEN_2).unwrap()),
+        Ok("KLOPRSUZ".to_string())
+    );
+}
+
+#[test]
+fn test_unknown_glyph() {
+    let screen = Screen::parse(&SCREEN.replace(".##..###", ".##..#.#")).unwrap();
+    let err = recognize(&screen).unwrap_err();
+
+    assert_eq!(
+        err,
+        OcrError::UnknownGlyph {
+            index: 1,
+            bitmap: "#.#.\n#..#\n###.\n#..#\n#..#\n###.\n".to_string()
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "unknown glyph at position 1:\n#.#.\n#..#\n###.\n#..#\n#..#\n###.\n"
+    );
+}
+
+#[test]
+fn test_invalid_size() {
+    assert_eq!(
+        recognize(&Screen::parse("#..#.#\n").unwrap()),
+        Err(OcrError::InvalidSize {
+            width: 6,
+            height: 1
+        })
+    );
+}
+
+#[test]
+fn test_draw() {
+    let program = InstructionSet::default()
+        .parse("addx 1\nnoop\naddx 30\n")
+        .unwrap();
+    let screen = Screen::draw(Cpu::new(&program));
+
+    assert_eq!(screen.width(), 40);
+    assert_eq!(screen.height(), 6);
+    assert_eq!(
+        screen.to_string().lines().next().unwrap(),
+        "####...................................."
+    );
+    assert_eq!(
+        screen.to_string().lines().nth(1).unwrap(),
+        "........................................"
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(
+        Screen::parse("#..#\n#.#\n").unwrap_err(),
+        ScreenError::RaggedLine {
+            line: 2,
+            width: 3,
+            expected: 4
+        }
+    );
+    assert_eq!(
+        Screen::parse("#..#\n#.x#\n").unwrap_err(),
+        ScreenError::InvalidPixel {
+            line: 2,
+            column: 3,
+            pixel: 'x'
+        }
+    );
+}
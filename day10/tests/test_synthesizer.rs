@@ -0,0 +1,64 @@
+use day10::cpu::Cpu;
+use day10::crt::Screen;
+use day10::ocr::recognize;
+use day10::synthesizer::{synthesize, synthesize_text, text_screen, SynthesisError};
+
+#[test]
+fn test_synthesize_text() {
+    for text in ["EHZFZHCZ", "RGLRBZAU", "FZBPBFZF"] {
+        let program = synthesize_text(text).unwrap();
+        let screen = Screen::draw(Cpu::new(&program));
+
+        assert_eq!(screen, text_screen(text).unwrap());
+        assert_eq!(recognize(&screen), Ok(text.to_string()));
+        // An addx draws two pixels, so at most 120 instructions are needed
+        assert!(program.len() <= 120);
+    }
+}
+
+#[test]
+fn test_shortest_program() {
+    let mut pattern = "###".to_string() + &".".repeat(37) + "\n";
+    pattern += &(".".repeat(40) + "\n").repeat(5);
+    let program = synthesize(&Screen::parse(&pattern).unwrap()).unwrap();
+
+    // Keep X at 1 for the 3 first pixels, then move the sprite out of the screen and halt
+    let source: Vec<String> = program
+        .iter()
+        .map(|instruction| instruction.to_string())
+        .collect();
+    assert_eq!(source, vec!["noop", "addx -3"]);
+}
+
+#[test]
+fn test_impossible() {
+    // X starts at 1, so the first pixel is always lit
+    assert_eq!(
+        synthesize_text("ABC").unwrap_err(),
+        SynthesisError::Impossible { line: 0, column: 0 }
+    );
+    // The first two pixels are drawn with X at 1, the second one cannot be dark
+    assert_eq!(
+        synthesize_text("H").unwrap_err(),
+        SynthesisError::Impossible { line: 0, column: 1 }
+    );
+}
+
+#[test]
+fn test_invalid_targets() {
+    assert_eq!(
+        synthesize_text("EHZFZHCZE").unwrap_err(),
+        SynthesisError::TextTooLong { length: 9 }
+    );
+    assert_eq!(
+        synthesize_text("EQ").unwrap_err(),
+        SynthesisError::UnsupportedLetter('Q')
+    );
+    assert_eq!(
+        synthesize(&Screen::parse("###\n").unwrap()).unwrap_err(),
+        SynthesisError::InvalidSize {
+            width: 3,
+            height: 1
+        }
+    );
+}
@@ -0,0 +1,71 @@
+use crate::simulation::Simulation;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+/// Graphviz description of the monkeys throwing the items to each other. Every edge is weighted
+/// and labelled with the number of items thrown along it during the simulation: green edges are
+/// followed when the test succeeds, red edges when it fails and dashed edges were never used.
+pub fn to_dot(simulation: &Simulation) -> String {
+    let throws = simulation.throws();
+    let mut dot = String::from("digraph monkeys {\n");
+
+    for (index, inspections) in simulation.inspections().iter().enumerate() {
+        writeln!(
+            dot,
+            "    monkey{} [label=\"Monkey {}\\n{} inspections\"];",
+            index, index, inspections
+        )
+        .unwrap();
+    }
+
+    for (index, monkey) in simulation.monkeys().iter().enumerate() {
+        let mut targets = vec![(monkey.test.true_target, "green")];
+        if monkey.test.false_target == monkey.test.true_target {
+            targets[0].1 = "black";
+        } else {
+            targets.push((monkey.test.false_target, "red"));
+        }
+
+        for (target, color) in targets {
+            let count = throws[index][target];
+            let style = if count == 0 { ", style=dashed" } else { "" };
+            writeln!(
+                dot,
+                "    monkey{} -> monkey{} [label=\"{}\", weight={}, color={}{}];",
+                index, target, count, count, color, style
+            )
+            .unwrap();
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Write one CSV line per simulated round with the number of items held by every monkey at the
+/// end of the round then the number of items inspected by every monkey during the round. The
+/// rounds skipped after a cycle was detected only repeat the cycle and are left out, and so are
+/// the rounds of a simulation not recording them.
+pub fn write_csv<W: Write>(simulation: &Simulation, writer: &mut W) -> io::Result<()> {
+    let monkey_count = simulation.inspections().len();
+    let header: Vec<String> = (0..monkey_count)
+        .map(|index| format!("queue_{}", index))
+        .chain((0..monkey_count).map(|index| format!("inspections_{}", index)))
+        .collect();
+    writeln!(writer, "round,{}", header.join(","))?;
+
+    for round in 1..=simulation.simulated_rounds() {
+        let Some(stats) = simulation.round_stats(round) else {
+            break;
+        };
+        let values: Vec<String> = stats
+            .queue_lengths
+            .iter()
+            .chain(&stats.inspections)
+            .map(|value| value.to_string())
+            .collect();
+        writeln!(writer, "{},{}", round, values.join(","))?;
+    }
+
+    Ok(())
+}
@@ -1,4 +1,6 @@
 pub mod parser;
 pub mod model;
 pub mod utils;
-pub mod logic;
\ No newline at end of file
+pub mod logic;
+pub mod simulation;
+pub mod analytics;
\ No newline at end of file
@@ -1,26 +1,59 @@
-use crate::model::{Monkey, Operand, Operation, Operator, Test};
+use crate::model::{
+    EvaluationError, Expression, Monkey, Operation, Operator, Test, UnsoundReduction,
+};
+use crate::simulation::Relief;
 
-impl Operation {
-    pub fn apply_operation(&self, old: i64) -> i64 {
-        let left = match &self.left {
-            Operand::Old => old,
-            Operand::Value(value) => *value,
-        };
-
-        let right = match &self.right {
-            Operand::Old => old,
-            Operand::Value(value) => *value,
-        };
+impl Expression {
+    pub fn evaluate(&self, old: i64) -> Result<i64, EvaluationError> {
+        match self {
+            Expression::Old => Ok(old),
+            Expression::Value(value) => Ok(*value),
+            Expression::Negation(expression) => expression
+                .evaluate(old)?
+                .checked_neg()
+                .ok_or(EvaluationError::Overflow),
+            Expression::Binary(left, operator, right) => {
+                let left = left.evaluate(old)?;
+                let right = right.evaluate(old)?;
+                if right == 0 && matches!(operator, Operator::Division | Operator::Modulo) {
+                    return Err(EvaluationError::DivisionByZero);
+                }
+                match operator {
+                    Operator::Addition => left.checked_add(right),
+                    Operator::Subtraction => left.checked_sub(right),
+                    Operator::Multiplication => left.checked_mul(right),
+                    Operator::Division => left.checked_div(right),
+                    Operator::Modulo => left.checked_rem(right),
+                }
+                .ok_or(EvaluationError::Overflow)
+            }
+        }
+    }
 
-        match &self.operator {
-            Operator::Addition => left + right,
-            Operator::Subtraction => left - right,
-            Operator::Multiplication => left * right,
-            Operator::Division => left / right,
+    /// Find an operator for which computing the expression on a worry level reduced modulo the
+    /// product of the divisibility tests does not give the reduced result. Additions,
+    /// subtractions, multiplications and negations are polynomials, which are compatible with
+    /// congruences, divisions and modulos are not.
+    pub fn unsound_operator(&self) -> Option<Operator> {
+        match self {
+            Expression::Old | Expression::Value(_) => None,
+            Expression::Negation(expression) => expression.unsound_operator(),
+            Expression::Binary(left, operator, right) => match operator {
+                Operator::Division | Operator::Modulo => Some(*operator),
+                _ => left
+                    .unsound_operator()
+                    .or_else(|| right.unsound_operator()),
+            },
         }
     }
 }
 
+impl Operation {
+    pub fn apply_operation(&self, old: i64) -> Result<i64, EvaluationError> {
+        self.expression.evaluate(old)
+    }
+}
+
 impl Test {
     pub fn get_target(&self, worry_level: i64) -> usize {
         if worry_level % self.quotient == 0 {
@@ -34,36 +67,50 @@ impl Test {
 impl Monkey {
     /// Inspect an item and return a tuple containing the target monkey and the new worry level.
     ///
-    /// Return none if the monkey no longer has an item to inspect
-    pub fn inspect(&mut self, apply_division: bool, lcm: Option<i64>) -> Option<(usize, i64)> {
+    /// Return none if the monkey no longer has an item to inspect. The relief is applied to the
+    /// new worry level, then the remainder modulo `modulus` is kept if it is given.
+    /// `SimulationBuilder` checks that the reduction preserves the tests.
+    pub fn inspect(
+        &mut self,
+        relief: Relief,
+        modulus: Option<i64>,
+    ) -> Result<Option<(usize, i64)>, EvaluationError> {
         // take the first item in the list
-        let to_inspect = self.items.pop_front();
-
-        match to_inspect {
-            None => None,
-            Some(worry_level) => {
-                // Add 1 to the number of inspected items
-                self.inspected_items += 1;
+        let Some(worry_level) = self.items.pop_front() else {
+            return Ok(None);
+        };
 
-                // apply the operation
-                let mut worry_level = self.operation.apply_operation(worry_level);
+        // Add 1 to the number of inspected items
+        self.inspected_items += 1;
 
-                // Only keep the reminder if requested
-                if let Some(lcm) = lcm {
-                    worry_level = worry_level % lcm;
-                }
+        // apply the operation, then the relief
+        let mut worry_level = relief.apply(self.operation.apply_operation(worry_level)?);
 
-                // Make the divide by three division if requested
-                if apply_division {
-                    worry_level = worry_level / 3;
-                }
+        // Only keep the reminder if requested
+        if let Some(modulus) = modulus {
+            worry_level %= modulus;
+        }
 
-                // Get the target
-                let target = self.test.get_target(worry_level);
+        // Get the target
+        let target = self.test.get_target(worry_level);
 
-                // Return the result
-                Some((target, worry_level))
-            }
-        }
+        Ok(Some((target, worry_level)))
     }
 }
+
+/// Check that the worry levels can be reduced modulo the product of the divisibility tests
+pub fn check_modular_reduction(monkeys: &[Monkey]) -> Result<(), UnsoundReduction> {
+    monkeys
+        .iter()
+        .find_map(|monkey| {
+            monkey
+                .operation
+                .expression
+                .unsound_operator()
+                .map(|operator| UnsoundReduction {
+                    monkey: monkey.number,
+                    operator,
+                })
+        })
+        .map_or(Ok(()), Err)
+}
@@ -1,10 +1,12 @@
 use clap::Parser;
+use day11::analytics::{to_dot, write_csv};
 use day11::model::Monkey;
 use day11::parser::parse_data;
-use day11::utils::{display_monkeys, two_max};
-use std::fs;
+use day11::simulation::{Reduction, Relief, Simulation, DEFAULT_HISTORY_LIMIT};
+use day11::utils::{display_monkeys, format_monkeys};
+use std::fs::{self, File};
+use std::io::BufWriter;
 use std::path::PathBuf;
-use euc_lib::Lcm;
 
 #[derive(Parser, Debug, Eq, PartialEq, Clone)]
 struct Cli {
@@ -12,67 +14,205 @@ struct Cli {
     #[arg(short, long, default_value_t = false)]
     verbose: bool,
 
+    /// Run a custom simulation with this number of rounds
+    #[arg(short, long)]
+    rounds: Option<usize>,
+
+    /// Run a custom simulation dividing the worry levels by this number after every inspection,
+    /// 1 for no relief
+    #[arg(long)]
+    relief: Option<i64>,
+
+    /// Run a custom simulation reducing the worry levels: none, lcm or a modulus
+    #[arg(long, value_parser = parse_reduction)]
+    reduction: Option<ReductionArg>,
+
+    /// Display the monkeys visited by every item in the custom simulation
+    #[arg(short, long, default_value_t = false)]
+    trajectories: bool,
+
+    /// Display the number of items inspected by every monkey in every round of the custom
+    /// simulation
+    #[arg(long, default_value_t = false)]
+    per_round: bool,
+
+    /// Skip the repeated rounds once the custom simulation loops
+    #[arg(short, long, default_value_t = false)]
+    detect_cycles: bool,
+
+    /// Stop looking for a cycle after this number of rounds without one
+    #[arg(long, default_value_t = DEFAULT_HISTORY_LIMIT)]
+    history_limit: usize,
+
+    /// Write the throw network of the custom simulation to this Graphviz file
+    #[arg(long)]
+    dot: Option<PathBuf>,
+
+    /// Write the queue lengths and inspections of every round of the custom simulation to this
+    /// CSV file
+    #[arg(long)]
+    csv: Option<PathBuf>,
+
+    /// Write the monkeys at the end of the custom simulation to this file, in the input format
+    #[arg(long)]
+    dump: Option<PathBuf>,
+
     /// File to parse
     #[arg(default_value = "input")]
     path: PathBuf,
 }
 
-fn solve_part_one(verbose: bool, mut monkeys: Vec<Monkey>) {
+/// Wrapper around `Reduction` to parse it from the command line
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+struct ReductionArg(Reduction);
+
+fn parse_reduction(value: &str) -> Result<ReductionArg, String> {
+    match value {
+        "none" => Ok(ReductionArg(Reduction::None)),
+        "lcm" => Ok(ReductionArg(Reduction::Lcm)),
+        modulus => modulus
+            .parse()
+            .map(|modulus| ReductionArg(Reduction::Modulo(modulus)))
+            .map_err(|_| format!("expected none, lcm or a modulus, got {}", value)),
+    }
+}
+
+fn solve_part_one(verbose: bool, monkeys: Vec<Monkey>) {
+    let mut simulation = Simulation::builder(monkeys).build().unwrap();
+
     if verbose {
-        display_monkeys(0, &monkeys);
+        display_monkeys(0, &simulation.monkeys());
     }
-    for round in 1..=20 {
-        for i_monkey in 0..monkeys.len() {
-            loop {
-                let inspect_result = monkeys[i_monkey].inspect(true, None);
-
-                match inspect_result {
-                    None => break,
-                    Some((target, worry_level)) => {
-                        // Add the item to the target monkey
-                        monkeys[target].items.push_back(worry_level)
-                    }
-                }
-            }
+    while !simulation.is_finished() {
+        if let Err(err) = simulation.step() {
+            println!("Part one cannot be solved: {}", err);
+            return;
         }
 
         if verbose {
-            display_monkeys(round, &monkeys);
+            display_monkeys(simulation.round(), &simulation.monkeys());
         }
     }
 
-    // Get the two max inspection times
-    let (max1, max2) = two_max(&monkeys);
+    println!("Part one solution: {:?}", simulation.monkey_business());
+}
 
-    println!("Part one solution: {:?}", max1 * max2);
+fn solve_part_two(monkeys: Vec<Monkey>) {
+    // Without the modular reduction, the worry levels overflow
+    let simulation = Simulation::builder(monkeys)
+        .rounds(10_000)
+        .relief(Relief::None)
+        .reduction(Reduction::Lcm)
+        .build()
+        .and_then(|mut simulation| simulation.run().map(|_| simulation));
+
+    match simulation {
+        Ok(simulation) => {
+            println!("Part two solution: {:?}", simulation.monkey_business());
+        }
+        Err(err) => println!("Part two cannot be solved: {}", err),
+    }
 }
 
-fn solve_part_two(mut monkeys: Vec<Monkey>) {
-    // Compute lcm of monkeys divisibility tests
-    let lcm = monkeys.iter().map(|monkey| {
-        monkey.test.quotient
-    }).fold(1, |acc, x| euc_lib::I64::lcm(acc, x));
-
-    for _ in 1..=10_000 {
-        for i_monkey in 0..monkeys.len() {
-            loop {
-                let inspect_result = monkeys[i_monkey].inspect(false, Some(lcm));
-
-                match inspect_result {
-                    None => break,
-                    Some((target, worry_level)) => {
-                        // Add the item to the target monkey
-                        monkeys[target].items.push_back(worry_level)
-                    }
-                }
-            }
+fn solve_custom(args: &Cli, monkeys: Vec<Monkey>) {
+    let mut builder = Simulation::builder(monkeys)
+        .track_items(args.trajectories)
+        .record_rounds(args.per_round || args.csv.is_some())
+        .detect_cycles(args.detect_cycles)
+        .history_limit(args.history_limit);
+    if let Some(rounds) = args.rounds {
+        builder = builder.rounds(rounds);
+    }
+    match args.relief {
+        Some(1) => builder = builder.relief(Relief::None),
+        Some(divisor) => builder = builder.relief(Relief::DivideBy(divisor)),
+        None => {}
+    }
+    if let Some(ReductionArg(reduction)) = args.reduction {
+        builder = builder.reduction(reduction);
+    }
+
+    let mut simulation = match builder.build() {
+        Ok(simulation) => simulation,
+        Err(err) => {
+            println!("Custom simulation cannot be run: {}", err);
+            return;
         }
+    };
+    if let Err(err) = simulation.run() {
+        println!("Custom simulation failed: {}", err);
+        return;
     }
 
-    // Get the two max inspection times
-    let (max1, max2) = two_max(&monkeys);
+    if let Some(cycle) = simulation.cycle() {
+        println!(
+            "The rounds repeat every {} rounds from round {}",
+            cycle.length, cycle.start
+        );
+    } else if args.detect_cycles && simulation.simulated_rounds() > args.history_limit {
+        println!(
+            "No cycle found in the first {} rounds, the next rounds were all simulated",
+            args.history_limit
+        );
+    }
+    if args.per_round {
+        for round in 1..=simulation.simulated_rounds() {
+            println!(
+                "Round {}: {:?}",
+                round,
+                simulation.round_inspections(round).unwrap()
+            );
+        }
+        if let Some(cycle) = simulation.cycle() {
+            if simulation.simulated_rounds() < simulation.round() {
+                println!(
+                    "Rounds {} to {} repeat rounds {} to {}",
+                    simulation.simulated_rounds() + 1,
+                    simulation.round(),
+                    cycle.start + 1,
+                    cycle.start + cycle.length
+                );
+            }
+        }
+    }
+    if args.trajectories {
+        for item in 0..simulation.item_count() {
+            let hops: Vec<String> = simulation
+                .trajectory(item)
+                .unwrap()
+                .iter()
+                .map(|hop| format!("{} ({})", hop.monkey, hop.worry_level))
+                .collect();
+            println!("Item {}: {}", item, hops.join(" -> "));
+        }
+        if let Some(cycle) = simulation.cycle() {
+            if simulation.simulated_rounds() < simulation.round() {
+                println!(
+                    "The trajectories stop at round {}, rounds {} to {} repeat rounds {} to {}",
+                    simulation.simulated_rounds(),
+                    simulation.simulated_rounds() + 1,
+                    simulation.round(),
+                    cycle.start + 1,
+                    cycle.start + cycle.length
+                );
+            }
+        }
+    }
+    if let Some(path) = &args.dot {
+        fs::write(path, to_dot(&simulation)).unwrap();
+    }
+    if let Some(path) = &args.csv {
+        let mut writer = BufWriter::new(File::create(path).unwrap());
+        write_csv(&simulation, &mut writer).unwrap();
+    }
+    if let Some(path) = &args.dump {
+        fs::write(path, format_monkeys(&simulation.monkeys())).unwrap();
+    }
 
-    println!("Part one solution: {:?}", max1 * max2);
+    println!(
+        "Custom simulation solution: {:?}",
+        simulation.monkey_business()
+    );
 }
 
 fn main() {
@@ -83,4 +223,17 @@ fn main() {
 
     solve_part_one(args.verbose, monkeys.clone());
     solve_part_two(monkeys.clone());
+
+    if args.rounds.is_some()
+        || args.relief.is_some()
+        || args.reduction.is_some()
+        || args.trajectories
+        || args.per_round
+        || args.detect_cycles
+        || args.dot.is_some()
+        || args.csv.is_some()
+        || args.dump.is_some()
+    {
+        solve_custom(&args, monkeys);
+    }
 }
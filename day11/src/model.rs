@@ -1,27 +1,13 @@
 use std::collections::VecDeque;
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Eq, PartialEq, Clone)]
-pub enum Operand {
-    Old,
-    Value(i64),
-}
-
-impl<'a> From<&'a str> for Operand {
-    fn from(value: &'a str) -> Self {
-        match value {
-            "old" => Self::Old,
-            number => Self::Value(number.parse().unwrap()),
-        }
-    }
-}
-
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
 pub enum Operator {
     Addition,
     Subtraction,
     Multiplication,
     Division,
+    Modulo,
 }
 
 impl<'a> From<&'a str> for Operator {
@@ -30,17 +16,134 @@ impl<'a> From<&'a str> for Operator {
             "+" => Self::Addition,
             "-" => Self::Subtraction,
             "*" => Self::Multiplication,
-            "/" | "%" => Self::Division,
+            "/" => Self::Division,
+            "%" => Self::Modulo,
             _ => unreachable!(),
         }
     }
 }
 
+impl Operator {
+    /// Binding strength of the operator, higher binds tighter
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Addition | Self::Subtraction => 1,
+            Self::Multiplication | Self::Division | Self::Modulo => 2,
+        }
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Self::Addition => "+",
+            Self::Subtraction => "-",
+            Self::Multiplication => "*",
+            Self::Division => "/",
+            Self::Modulo => "%",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+/// Arithmetic expression computing the new worry level from the `old` one
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Expression {
+    Old,
+    Value(i64),
+    Negation(Box<Expression>),
+    Binary(Box<Expression>, Operator, Box<Expression>),
+}
+
+impl Expression {
+    pub fn binary(left: Expression, operator: Operator, right: Expression) -> Self {
+        Self::Binary(Box::new(left), operator, Box::new(right))
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Old => 4,
+            Self::Value(value) if *value >= 0 => 4,
+            Self::Value(_) | Self::Negation(_) => 3,
+            Self::Binary(_, operator, _) => operator.precedence(),
+        }
+    }
+
+    /// Write the operand, between parentheses if it binds weaker than `precedence`
+    fn fmt_operand(&self, f: &mut Formatter<'_>, precedence: u8) -> std::fmt::Result {
+        if self.precedence() < precedence {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+/// Written with the fewest parentheses that parse back to the same expression
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Old => write!(f, "old"),
+            Self::Value(value) => write!(f, "{}", value),
+            Self::Negation(expression) => {
+                write!(f, "-")?;
+                expression.fmt_operand(f, 3)
+            }
+            Self::Binary(left, operator, right) => {
+                // The operators are left associative, so an operand of the same precedence only
+                // needs parentheses on the right
+                left.fmt_operand(f, operator.precedence())?;
+                write!(f, " {} ", operator)?;
+                right.fmt_operand(f, operator.precedence() + 1)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Operation {
-    pub left: Operand,
+    pub expression: Expression,
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "new = {}", self.expression)
+    }
+}
+
+/// Why an expression cannot be evaluated
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum EvaluationError {
+    /// The result does not fit in 64 bits
+    Overflow,
+    DivisionByZero,
+}
+
+impl Display for EvaluationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvaluationError::Overflow => write!(f, "the worry level overflows"),
+            EvaluationError::DivisionByZero => write!(f, "the worry level is divided by zero"),
+        }
+    }
+}
+
+/// An operation that breaks the modular reduction of the worry levels
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct UnsoundReduction {
+    pub monkey: usize,
     pub operator: Operator,
-    pub right: Operand,
+}
+
+impl Display for UnsoundReduction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the operation of monkey {} uses {:?}, which does not preserve the worry levels modulo \
+             the divisibility tests",
+            self.monkey, self.operator
+        )
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -50,6 +153,14 @@ pub struct Test {
     pub false_target: usize,
 }
 
+impl Display for Test {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "  Test: divisible by {}", self.quotient)?;
+        writeln!(f, "    If true: throw to monkey {}", self.true_target)?;
+        write!(f, "    If false: throw to monkey {}", self.false_target)
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Monkey {
     pub number: usize,
@@ -59,8 +170,17 @@ pub struct Monkey {
     pub inspected_items: usize
 }
 
+/// Written in the puzzle input format, without the trailing line ending
 impl Display for Monkey {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Monkey {}: {:?}", self.number, self.items)
+        writeln!(f, "Monkey {}:", self.number)?;
+        let items: Vec<String> = self.items.iter().map(|item| item.to_string()).collect();
+        if items.is_empty() {
+            writeln!(f, "  Starting items:")?;
+        } else {
+            writeln!(f, "  Starting items: {}", items.join(", "))?;
+        }
+        writeln!(f, "  Operation: {}", self.operation)?;
+        write!(f, "{}", self.test)
     }
 }
\ No newline at end of file
@@ -1,8 +1,9 @@
-use crate::model::{Monkey, Operand, Operation, Operator, Test};
+use crate::model::{Expression, Monkey, Operation, Operator, Test};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::{digit1, line_ending, space0, space1};
-use nom::combinator::{map, map_res};
+use nom::character::complete::{digit1, line_ending, space0};
+use nom::character::complete::i64 as integer;
+use nom::combinator::{map, map_res, opt};
 use nom::multi::{many0, many1};
 use nom::sequence::{delimited, pair, preceded, tuple};
 use nom::{IResult, Parser};
@@ -13,14 +14,61 @@ pub fn parse_starting_items_line(input: &str) -> IResult<&str, Vec<i64>> {
     map(
         delimited(
             tuple((space0, tag("Starting items:"), space0)),
-            pair(digit1, many0(preceded(pair(tag(","), space0), digit1))),
+            opt(pair(integer, many0(preceded(pair(tag(","), space0), integer)))),
             line_ending,
         ),
-        |(first, remaining): (&str, Vec<&str>)| {
-            std::iter::once(first)
-                .chain(remaining.into_iter())
-                .map(|elt| elt.parse().unwrap())
-                .collect()
+        |items| match items {
+            // A monkey may hold no items in a state dumped after a round
+            None => vec![],
+            Some((first, remaining)) => std::iter::once(first).chain(remaining).collect(),
+        },
+    )
+    .parse(input)
+}
+
+fn parse_factor(input: &str) -> IResult<&str, Expression> {
+    delimited(
+        space0,
+        alt((
+            map(tag("old"), |_| Expression::Old),
+            map_res(digit1, |val| i64::from_str(val).map(Expression::Value)),
+            map(preceded(tag("-"), parse_factor), |expression| {
+                Expression::Negation(Box::new(expression))
+            }),
+            delimited(tag("("), parse_expression, tag(")")),
+        )),
+        space0,
+    )
+    .parse(input)
+}
+
+fn parse_term(input: &str) -> IResult<&str, Expression> {
+    map(
+        pair(
+            parse_factor,
+            many0(pair(alt((tag("*"), tag("/"), tag("%"))), parse_factor)),
+        ),
+        |(first, remaining)| {
+            remaining.into_iter().fold(first, |left, (operator, right)| {
+                Expression::binary(left, Operator::from(operator), right)
+            })
+        },
+    )
+    .parse(input)
+}
+
+/// Parse an arithmetic expression over `old`, with the usual precedence: unary minus, then
+/// `*`, `/` and `%`, then `+` and `-`
+pub fn parse_expression(input: &str) -> IResult<&str, Expression> {
+    map(
+        pair(
+            parse_term,
+            many0(pair(alt((tag("+"), tag("-"))), parse_term)),
+        ),
+        |(first, remaining)| {
+            remaining.into_iter().fold(first, |left, (operator, right)| {
+                Expression::binary(left, Operator::from(operator), right)
+            })
         },
     )
     .parse(input)
@@ -30,20 +78,10 @@ pub fn parse_operation_line(input: &str) -> IResult<&str, Operation> {
     map(
         delimited(
             tuple((space0, tag("Operation: new ="), space0)),
-            tuple((
-                alt((tag("old"), digit1)),
-                space1,
-                alt((tag("+"), tag("-"), tag("*"), tag("/"), tag("%"))),
-                space1,
-                alt((tag("old"), digit1)),
-            )),
+            parse_expression,
             line_ending,
         ),
-        |(left, _, operator, _, right)| Operation {
-            left: Operand::from(left),
-            operator: Operator::from(operator),
-            right: Operand::from(right),
-        },
+        |expression| Operation { expression },
     )
     .parse(input)
 }
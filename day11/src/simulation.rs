@@ -0,0 +1,533 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Formatter};
+
+use euc_lib::Lcm;
+
+use crate::logic::check_modular_reduction;
+use crate::model::{EvaluationError, Monkey, UnsoundReduction};
+
+/// How the worry level decreases after an inspection
+#[derive(Debug, Clone, Copy)]
+pub enum Relief {
+    None,
+    DivideBy(i64),
+    Custom(fn(i64) -> i64),
+}
+
+impl Relief {
+    pub fn apply(&self, worry_level: i64) -> i64 {
+        match self {
+            Relief::None => worry_level,
+            Relief::DivideBy(divisor) => worry_level / divisor,
+            Relief::Custom(relief) => relief(worry_level),
+        }
+    }
+}
+
+/// How the worry levels are kept small
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Reduction {
+    None,
+    /// Modulo the least common multiple of the divisibility tests
+    Lcm,
+    /// Modulo a multiple of every divisibility test
+    Modulo(i64),
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SimulationError {
+    /// An operation does not preserve the worry levels modulo the reduction
+    UnsoundOperation(UnsoundReduction),
+    /// The modulus is not a multiple of the divisibility test of a monkey
+    IncompatibleModulus { monkey: usize, quotient: i64 },
+    /// A relief does not preserve the worry levels modulo the reduction
+    ReliefWithReduction,
+    /// The relief divides the worry levels by zero or a negative number
+    InvalidDivisor(i64),
+    /// The reduction keeps the remainders modulo zero or a negative number
+    InvalidModulus(i64),
+    /// The operation of a monkey cannot be computed during a round
+    Evaluation {
+        round: usize,
+        monkey: usize,
+        error: EvaluationError,
+    },
+}
+
+impl Display for SimulationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SimulationError::UnsoundOperation(err) => write!(f, "{}", err),
+            SimulationError::IncompatibleModulus { monkey, quotient } => write!(
+                f,
+                "the modulus is not a multiple of {}, the divisibility test of monkey {}",
+                quotient, monkey
+            ),
+            SimulationError::ReliefWithReduction => {
+                write!(f, "a relief cannot be combined with a reduction")
+            }
+            SimulationError::InvalidDivisor(divisor) => {
+                write!(f, "the relief divisor must be positive, got {}", divisor)
+            }
+            SimulationError::InvalidModulus(modulus) => {
+                write!(f, "the modulus must be positive, got {}", modulus)
+            }
+            SimulationError::Evaluation {
+                round,
+                monkey,
+                error,
+            } => write!(f, "round {}, monkey {}: {}", round, monkey, error),
+        }
+    }
+}
+
+/// An item arriving at a monkey
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Hop {
+    /// Round of the throw, 0 for the starting items
+    pub round: usize,
+    pub monkey: usize,
+    pub worry_level: i64,
+}
+
+/// The state after round `start + length` is the state after round `start`
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+/// Number of states kept by the cycle detection before it gives up
+pub const DEFAULT_HISTORY_LIMIT: usize = 100_000;
+
+/// Items held by every monkey, with their identifier and worry level
+type State = Vec<VecDeque<(usize, i64)>>;
+
+/// Identifiers of the items held by every monkey, in the order of their worry levels
+type Ids = Vec<VecDeque<usize>>;
+
+pub struct SimulationBuilder {
+    monkeys: Vec<Monkey>,
+    rounds: usize,
+    relief: Relief,
+    reduction: Reduction,
+    track_items: bool,
+    record_rounds: bool,
+    detect_cycles: bool,
+    history_limit: usize,
+}
+
+impl SimulationBuilder {
+    pub fn rounds(mut self, rounds: usize) -> Self {
+        self.rounds = rounds;
+        self
+    }
+
+    pub fn relief(mut self, relief: Relief) -> Self {
+        self.relief = relief;
+        self
+    }
+
+    pub fn reduction(mut self, reduction: Reduction) -> Self {
+        self.reduction = reduction;
+        self
+    }
+
+    /// Record the monkeys visited by every item
+    pub fn track_items(mut self, track_items: bool) -> Self {
+        self.track_items = track_items;
+        self
+    }
+
+    /// Keep the statistics of every round
+    pub fn record_rounds(mut self, record_rounds: bool) -> Self {
+        self.record_rounds = record_rounds;
+        self
+    }
+
+    /// Look for a round after which the items are held like after a previous round, in order to
+    /// skip the rounds repeating the cycle
+    pub fn detect_cycles(mut self, detect_cycles: bool) -> Self {
+        self.detect_cycles = detect_cycles;
+        self
+    }
+
+    /// Stop looking for a cycle after this number of rounds without one
+    pub fn history_limit(mut self, history_limit: usize) -> Self {
+        self.history_limit = history_limit;
+        self
+    }
+
+    pub fn build(self) -> Result<Simulation, SimulationError> {
+        if let Relief::DivideBy(divisor) = self.relief {
+            if divisor <= 0 {
+                return Err(SimulationError::InvalidDivisor(divisor));
+            }
+        }
+
+        let modulus = match self.reduction {
+            Reduction::None => None,
+            Reduction::Lcm => Some(
+                self.monkeys
+                    .iter()
+                    .map(|monkey| monkey.test.quotient)
+                    .fold(1, euc_lib::I64::lcm),
+            ),
+            Reduction::Modulo(modulus) => {
+                if modulus <= 0 {
+                    return Err(SimulationError::InvalidModulus(modulus));
+                }
+                if let Some(monkey) = self
+                    .monkeys
+                    .iter()
+                    .find(|monkey| modulus % monkey.test.quotient != 0)
+                {
+                    return Err(SimulationError::IncompatibleModulus {
+                        monkey: monkey.number,
+                        quotient: monkey.test.quotient,
+                    });
+                }
+                Some(modulus)
+            }
+        };
+        if modulus.is_some() {
+            check_modular_reduction(&self.monkeys).map_err(SimulationError::UnsoundOperation)?;
+            if !matches!(self.relief, Relief::None) {
+                return Err(SimulationError::ReliefWithReduction);
+            }
+        }
+
+        // Number the items in the order of the input
+        let mut next_id = 0;
+        let mut trajectories = vec![];
+        let ids: Ids = self
+            .monkeys
+            .iter()
+            .map(|monkey| {
+                monkey
+                    .items
+                    .iter()
+                    .map(|worry_level| {
+                        trajectories.push(vec![Hop {
+                            round: 0,
+                            monkey: monkey.number,
+                            worry_level: *worry_level,
+                        }]);
+                        next_id += 1;
+                        next_id - 1
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut simulation = Simulation {
+            rounds: self.rounds,
+            relief: self.relief,
+            modulus,
+            round: 0,
+            simulated_rounds: 0,
+            true_throws: vec![0; self.monkeys.len()],
+            record_rounds: self.record_rounds,
+            round_stats: vec![],
+            trajectories: if self.track_items {
+                Some(trajectories)
+            } else {
+                None
+            },
+            history: None,
+            history_limit: self.history_limit,
+            cycle: None,
+            monkeys: self.monkeys,
+            ids,
+        };
+        for monkey in &mut simulation.monkeys {
+            monkey.inspected_items = 0;
+        }
+        if self.detect_cycles {
+            let state = simulation.state();
+            simulation.history = Some((vec![state.clone()], HashMap::from([(state, 0)])));
+        }
+
+        Ok(simulation)
+    }
+}
+
+/// What happened during a round
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RoundStats {
+    /// Number of items inspected by every monkey
+    pub inspections: Vec<usize>,
+    /// Number of items every monkey threw to the target of a successful test
+    pub true_throws: Vec<usize>,
+    /// Number of items held by every monkey at the end of the round
+    pub queue_lengths: Vec<usize>,
+}
+
+/// Simulation of the monkeys throwing the items around
+pub struct Simulation {
+    /// The monkeys with the worry levels of the items they hold and their number of inspections
+    monkeys: Vec<Monkey>,
+    ids: Ids,
+    rounds: usize,
+    relief: Relief,
+    modulus: Option<i64>,
+    /// Number of completed rounds
+    round: usize,
+    /// Number of rounds actually played
+    simulated_rounds: usize,
+    true_throws: Vec<usize>,
+    record_rounds: bool,
+    /// Statistics of every simulated round, the skipped rounds repeat the cycle. They are only
+    /// kept when recording the rounds or looking for a cycle.
+    round_stats: Vec<RoundStats>,
+    trajectories: Option<Vec<Vec<Hop>>>,
+    /// States after every simulated round and the round of every state
+    history: Option<(Vec<State>, HashMap<State, usize>)>,
+    history_limit: usize,
+    cycle: Option<Cycle>,
+}
+
+impl Simulation {
+    /// Simulation of the first part of the puzzle: 20 rounds, with the worry levels divided by 3
+    /// after every inspection
+    pub fn builder(monkeys: Vec<Monkey>) -> SimulationBuilder {
+        SimulationBuilder {
+            monkeys,
+            rounds: 20,
+            relief: Relief::DivideBy(3),
+            reduction: Reduction::None,
+            track_items: false,
+            record_rounds: false,
+            detect_cycles: false,
+            history_limit: DEFAULT_HISTORY_LIMIT,
+        }
+    }
+
+    /// Number of completed rounds
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    /// Number of rounds actually played, the later rounds repeating the cycle were skipped
+    pub fn simulated_rounds(&self) -> usize {
+        self.simulated_rounds
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.round >= self.rounds
+    }
+
+    /// The monkeys with the items they hold and the number of items they inspected
+    pub fn monkeys(&self) -> &[Monkey] {
+        &self.monkeys
+    }
+
+    /// Number of items inspected by every monkey since the start
+    pub fn inspections(&self) -> Vec<usize> {
+        self.monkeys
+            .iter()
+            .map(|monkey| monkey.inspected_items)
+            .collect()
+    }
+
+    fn state(&self) -> State {
+        self.monkeys
+            .iter()
+            .zip(&self.ids)
+            .map(|(monkey, ids)| {
+                ids.iter()
+                    .copied()
+                    .zip(monkey.items.iter().copied())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn set_state(&mut self, state: &State) {
+        for ((monkey, ids), items) in self.monkeys.iter_mut().zip(&mut self.ids).zip(state) {
+            *ids = items.iter().map(|(id, _)| *id).collect();
+            monkey.items = items.iter().map(|(_, worry_level)| *worry_level).collect();
+        }
+    }
+
+    /// Number of items thrown from every monkey to every monkey since the start, indexed by
+    /// thrower then target
+    pub fn throws(&self) -> Vec<Vec<usize>> {
+        let mut throws = vec![vec![0; self.monkeys.len()]; self.monkeys.len()];
+        for (index, monkey) in self.monkeys.iter().enumerate() {
+            throws[index][monkey.test.true_target] += self.true_throws[index];
+            throws[index][monkey.test.false_target] +=
+                monkey.inspected_items - self.true_throws[index];
+        }
+
+        throws
+    }
+
+    /// Statistics of a completed round, starting at 1, if the rounds are recorded
+    pub fn round_stats(&self, round: usize) -> Option<&RoundStats> {
+        if round == 0 || round > self.round {
+            return None;
+        }
+
+        let simulated = match self.cycle {
+            Some(cycle) if round > self.simulated_rounds => {
+                cycle.start + (round - cycle.start - 1) % cycle.length + 1
+            }
+            _ => round,
+        };
+        self.round_stats.get(simulated - 1)
+    }
+
+    /// Number of items inspected by every monkey during a round, starting at 1, if the rounds are
+    /// recorded
+    pub fn round_inspections(&self, round: usize) -> Option<&[usize]> {
+        self.round_stats(round)
+            .map(|stats| stats.inspections.as_slice())
+    }
+
+    /// Product of the two highest numbers of inspections
+    pub fn monkey_business(&self) -> u128 {
+        let mut inspections = self.inspections();
+        inspections.sort_unstable_by(|left, right| right.cmp(left));
+        inspections
+            .iter()
+            .take(2)
+            .map(|count| *count as u128)
+            .product()
+    }
+
+    /// Monkeys visited by the item during the simulated rounds, starting with the monkey holding
+    /// it at the start. The hops stop at the last simulated round: after a cycle is found, the
+    /// skipped rounds repeat the hops of the cycle.
+    pub fn trajectory(&self, item: usize) -> Option<&[Hop]> {
+        self.trajectories
+            .as_ref()
+            .and_then(|trajectories| trajectories.get(item))
+            .map(|hops| hops.as_slice())
+    }
+
+    pub fn item_count(&self) -> usize {
+        self.ids.iter().map(|ids| ids.len()).sum()
+    }
+
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Play one round. The simulation cannot go on after an error.
+    pub fn step(&mut self) -> Result<(), SimulationError> {
+        let round = self.round + 1;
+        let inspected: Vec<usize> = self.inspections();
+        let mut true_throws = vec![0; self.monkeys.len()];
+
+        for (index, true_throws) in true_throws.iter_mut().enumerate() {
+            while let Some((target, worry_level)) = self.monkeys[index]
+                .inspect(self.relief, self.modulus)
+                .map_err(|error| SimulationError::Evaluation {
+                    round,
+                    monkey: self.monkeys[index].number,
+                    error,
+                })?
+            {
+                let id = self.ids[index].pop_front().unwrap();
+                if worry_level % self.monkeys[index].test.quotient == 0 {
+                    *true_throws += 1;
+                }
+
+                self.monkeys[target].items.push_back(worry_level);
+                self.ids[target].push_back(id);
+                if let Some(trajectories) = &mut self.trajectories {
+                    trajectories[id].push(Hop {
+                        round,
+                        monkey: target,
+                        worry_level,
+                    });
+                }
+            }
+        }
+
+        self.round = round;
+        self.simulated_rounds = round;
+        for (total, count) in self.true_throws.iter_mut().zip(&true_throws) {
+            *total += count;
+        }
+        // The fast-forward adds up the statistics of the cycle
+        if self.record_rounds || self.history.is_some() {
+            self.round_stats.push(RoundStats {
+                inspections: self
+                    .monkeys
+                    .iter()
+                    .zip(inspected)
+                    .map(|(monkey, inspected)| monkey.inspected_items - inspected)
+                    .collect(),
+                true_throws,
+                queue_lengths: self.ids.iter().map(|ids| ids.len()).collect(),
+            });
+        }
+
+        let state = self.history.as_ref().map(|_| self.state());
+        if let (Some(state), Some((states, rounds))) = (state, &mut self.history) {
+            match rounds.get(&state) {
+                Some(start) => {
+                    self.cycle = Some(Cycle {
+                        start: *start,
+                        length: self.round - start,
+                    })
+                }
+                None if states.len() >= self.history_limit => {
+                    // Give up on the cycle, and on the statistics kept only to skip it
+                    self.history = None;
+                    if !self.record_rounds {
+                        self.round_stats = vec![];
+                    }
+                }
+                None => {
+                    rounds.insert(state.clone(), self.round);
+                    states.push(state);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Play the remaining rounds, skipping the repetitions of a cycle once it is found
+    pub fn run(&mut self) -> Result<(), SimulationError> {
+        while !self.is_finished() {
+            if let Some(cycle) = self.cycle {
+                self.fast_forward(cycle);
+                return Ok(());
+            }
+            self.step()?;
+        }
+
+        Ok(())
+    }
+
+    fn fast_forward(&mut self, cycle: Cycle) {
+        let remaining = self.rounds - self.round;
+        let cycle_stats = &self.round_stats[cycle.start..cycle.start + cycle.length];
+
+        // Whole cycles, then the beginning of the cycle
+        let repetitions = remaining / cycle.length;
+        let rest = remaining % cycle.length;
+        let skipped = |count: fn(&RoundStats, usize) -> usize, index: usize| {
+            let per_cycle: usize = cycle_stats.iter().map(|stats| count(stats, index)).sum();
+            let partial: usize = cycle_stats[..rest]
+                .iter()
+                .map(|stats| count(stats, index))
+                .sum();
+            repetitions * per_cycle + partial
+        };
+        for index in 0..self.monkeys.len() {
+            self.monkeys[index].inspected_items +=
+                skipped(|stats, index| stats.inspections[index], index);
+            self.true_throws[index] += skipped(|stats, index| stats.true_throws[index], index);
+        }
+
+        let (states, _) = self.history.as_ref().unwrap();
+        let state = states[cycle.start + rest].clone();
+        self.set_state(&state);
+        self.round = self.rounds;
+    }
+}
@@ -6,22 +6,14 @@ pub fn display_monkeys(round: usize, monkeys: &[Monkey]) {
         round
     );
     for monkey in monkeys {
-        println!("{}", monkey)
+        let items: Vec<String> = monkey.items.iter().map(|item| item.to_string()).collect();
+        println!("Monkey {}: {}", monkey.number, items.join(", "))
     }
     println!();
 }
 
-pub fn two_max(monkeys: &[Monkey]) -> (usize, usize) {
-    monkeys
-        .iter()
-        .map(|monkey| monkey.inspected_items)
-        .fold((0, 0), |(max1, max2), x| {
-            if x >= max1 {
-                (x, max1)
-            } else if x >= max2 {
-                (max1, x)
-            } else {
-                (max1, max2)
-            }
-        })
+/// Write the monkeys in the puzzle input format, so that they can be parsed again
+pub fn format_monkeys(monkeys: &[Monkey]) -> String {
+    let monkeys: Vec<String> = monkeys.iter().map(|monkey| format!("{}\n", monkey)).collect();
+    monkeys.join("\n")
 }
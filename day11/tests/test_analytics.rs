@@ -0,0 +1,96 @@
+use day11::analytics::{to_dot, write_csv};
+use day11::parser::parse_data;
+use day11::simulation::{Reduction, Relief, Simulation};
+
+static INPUT_EXAMPLE: &str = include_str!("../input_example");
+
+fn first_round() -> Simulation {
+    let mut simulation = Simulation::builder(parse_data(INPUT_EXAMPLE))
+        .rounds(1)
+        .build()
+        .unwrap();
+    simulation.run().unwrap();
+    simulation
+}
+
+#[test]
+fn test_throws() {
+    // Round 1 of the puzzle example
+    let simulation = first_round();
+    assert_eq!(
+        simulation.throws(),
+        vec![
+            vec![0, 0, 0, 2],
+            vec![4, 0, 0, 0],
+            vec![0, 1, 0, 2],
+            vec![0, 5, 0, 0],
+        ]
+    );
+}
+
+#[test]
+fn test_throws_total_inspections() {
+    let mut simulation = Simulation::builder(parse_data(INPUT_EXAMPLE))
+        .build()
+        .unwrap();
+    simulation.run().unwrap();
+
+    for (throws, inspections) in simulation.throws().iter().zip(simulation.inspections()) {
+        assert_eq!(throws.iter().sum::<usize>(), inspections);
+    }
+}
+
+#[test]
+fn test_to_dot() {
+    let dot = to_dot(&first_round());
+
+    assert!(dot.starts_with("digraph monkeys {\n"));
+    assert!(dot.ends_with("}\n"));
+    assert!(dot.contains("    monkey1 [label=\"Monkey 1\\n4 inspections\"];\n"));
+    assert!(dot.contains("    monkey0 -> monkey3 [label=\"2\", weight=2, color=red];\n"));
+    assert!(dot
+        .contains("    monkey0 -> monkey2 [label=\"0\", weight=0, color=green, style=dashed];\n"));
+    assert!(dot.contains("    monkey2 -> monkey1 [label=\"1\", weight=1, color=green];\n"));
+    assert_eq!(dot.matches("->").count(), 8);
+}
+
+#[test]
+fn test_write_csv() {
+    let mut simulation = Simulation::builder(parse_data(INPUT_EXAMPLE))
+        .rounds(2)
+        .record_rounds(true)
+        .build()
+        .unwrap();
+    simulation.run().unwrap();
+
+    let mut csv = vec![];
+    write_csv(&simulation, &mut csv).unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "round,queue_0,queue_1,queue_2,queue_3,inspections_0,inspections_1,inspections_2,inspections_3
+1,4,6,0,0,2,4,3,5
+2,5,5,0,0,4,6,1,5
+"
+    );
+}
+
+#[test]
+fn test_write_csv_skipped_rounds() {
+    let mut simulation = Simulation::builder(parse_data(INPUT_EXAMPLE))
+        .rounds(1_000_000_000_000)
+        .relief(Relief::None)
+        .reduction(Reduction::Lcm)
+        .detect_cycles(true)
+        .build()
+        .unwrap();
+    simulation.run().unwrap();
+
+    let cycle = simulation.cycle().unwrap();
+    assert_eq!(simulation.simulated_rounds(), cycle.start + cycle.length);
+
+    let mut csv = vec![];
+    write_csv(&simulation, &mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    // The skipped rounds only repeat the cycle and are left out
+    assert_eq!(csv.lines().count(), simulation.simulated_rounds() + 1);
+}
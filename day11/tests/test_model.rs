@@ -1,18 +1,71 @@
-use day11::model::{Operand, Operation, Operator, Test};
+use day11::logic::check_modular_reduction;
+use day11::model::{EvaluationError, Expression, Operation, Operator, Test, UnsoundReduction};
+use day11::parser::{parse_data, parse_expression};
+use day11::simulation::Relief;
+
+static MONKEYS: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = (old + 1) * -old
+  Test: divisible by 23
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 54
+  Operation: new = old / 2 + 6
+  Test: divisible by 19
+    If true: throw to monkey 0
+    If false: throw to monkey 0
+";
 
 #[test]
 pub fn test_apply_operation() {
     assert_eq!(Operation{
-        left: Operand::Old,
-        operator: Operator::Addition,
-        right: Operand::Old,
-    }.apply_operation(42), 84);
+        expression: Expression::binary(Expression::Old, Operator::Addition, Expression::Old),
+    }.apply_operation(42), Ok(84));
 
     assert_eq!(Operation{
-        left: Operand::Old,
-        operator: Operator::Multiplication,
-        right: Operand::Value(19),
-    }.apply_operation(42), 798);
+        expression: Expression::binary(Expression::Old, Operator::Multiplication, Expression::Value(19)),
+    }.apply_operation(42), Ok(798));
+}
+
+#[test]
+pub fn test_evaluate_expression() {
+    let evaluate = |input: &str, old: i64| parse_expression(input).unwrap().1.evaluate(old).unwrap();
+
+    assert_eq!(evaluate("old + 2 * old", 5), 15);
+    assert_eq!(evaluate("(old + 2) * old", 5), 35);
+    assert_eq!(evaluate("-old * -3", 5), 15);
+    assert_eq!(evaluate("old % 4 + old / 4", 10), 4);
+    assert_eq!(evaluate("old - 1 - 2", 10), 7);
+
+    let evaluate = |input: &str, old: i64| parse_expression(input).unwrap().1.evaluate(old);
+    assert_eq!(evaluate("old * old", 1 << 32), Err(EvaluationError::Overflow));
+    assert_eq!(evaluate("-old - 2", i64::MAX), Err(EvaluationError::Overflow));
+    assert_eq!(evaluate("old / (old - 5)", 5), Err(EvaluationError::DivisionByZero));
+    assert_eq!(evaluate("old % 0", 5), Err(EvaluationError::DivisionByZero));
+}
+
+#[test]
+pub fn test_modular_reduction() {
+    let monkeys = parse_data(MONKEYS);
+    assert_eq!(check_modular_reduction(&monkeys[..1]), Ok(()));
+    assert_eq!(
+        check_modular_reduction(&monkeys),
+        Err(UnsoundReduction {
+            monkey: 1,
+            operator: Operator::Division,
+        })
+    );
+
+    // Polynomials keep the worry levels congruent
+    let expression = parse_expression("-(old * old) + 3 * (old - 7)").unwrap().1;
+    assert_eq!(expression.unsound_operator(), None);
+    assert_eq!(
+        expression.evaluate(1234).unwrap().rem_euclid(91),
+        expression.evaluate(1234 % 91).unwrap().rem_euclid(91)
+    );
+    assert_eq!(parse_expression("old * (old % 5)").unwrap().1.unsound_operator(), Some(Operator::Modulo));
 }
 
 #[test]
@@ -27,4 +80,20 @@ pub fn test_get_target() {
         true_target: 1,
         false_target: 3,
     }.get_target(2080), 1)
-}
\ No newline at end of file
+}
+#[test]
+pub fn test_inspect() {
+    let mut monkey = parse_data(MONKEYS).remove(1);
+    // 54 / 2 + 6 = 33, relieved to 11
+    assert_eq!(monkey.inspect(Relief::DivideBy(3), None), Ok(Some((0, 11))));
+    assert_eq!(monkey.inspect(Relief::DivideBy(3), None), Ok(None));
+    assert_eq!(monkey.inspected_items, 1);
+
+    let mut monkey = parse_data(MONKEYS).remove(0);
+    // (79 + 1) * -79 = -6320, reduced modulo 23 * 19
+    assert_eq!(
+        monkey.inspect(Relief::None, Some(437)),
+        Ok(Some((1, -6320 % 437)))
+    );
+    assert_eq!(monkey.items, vec![98]);
+}
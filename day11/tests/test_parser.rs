@@ -1,5 +1,6 @@
-use day11::model::{Monkey, Operand, Operation, Operator, Test};
-use day11::parser::{parse_monkey, parse_monkey_line, parse_operation_line, parse_starting_items_line, parse_test_lines};
+use day11::model::{Expression, Monkey, Operation, Operator, Test};
+use day11::utils::format_monkeys;
+use day11::parser::{parse_data, parse_expression, parse_monkey, parse_monkey_line, parse_operation_line, parse_starting_items_line, parse_test_lines};
 
 #[test]
 fn test_parse_starting_item_line() {
@@ -20,9 +21,11 @@ fn test_parse_operation_line() {
         Ok((
             "",
             Operation {
-                left: Operand::Old,
-                operator: Operator::Addition,
-                right: Operand::Value(3),
+                expression: Expression::binary(
+                    Expression::Old,
+                    Operator::Addition,
+                    Expression::Value(3)
+                ),
             }
         ))
     );
@@ -31,9 +34,11 @@ fn test_parse_operation_line() {
         Ok((
             "",
             Operation {
-                left: Operand::Old,
-                operator: Operator::Multiplication,
-                right: Operand::Old,
+                expression: Expression::binary(
+                    Expression::Old,
+                    Operator::Multiplication,
+                    Expression::Old
+                ),
             }
         ))
     );
@@ -42,14 +47,60 @@ fn test_parse_operation_line() {
         Ok((
             "",
             Operation {
-                left: Operand::Old,
-                operator: Operator::Multiplication,
-                right: Operand::Value(19),
+                expression: Expression::binary(
+                    Expression::Old,
+                    Operator::Multiplication,
+                    Expression::Value(19)
+                ),
             }
         ))
     );
 }
 
+#[test]
+fn test_parse_expression() {
+    // Multiplication before addition
+    assert_eq!(
+        parse_expression("old + 2 * old"),
+        Ok((
+            "",
+            Expression::binary(
+                Expression::Old,
+                Operator::Addition,
+                Expression::binary(Expression::Value(2), Operator::Multiplication, Expression::Old)
+            )
+        ))
+    );
+    // Parentheses, unary minus and modulo
+    assert_eq!(
+        parse_expression("-(old - 3) % 7"),
+        Ok((
+            "",
+            Expression::binary(
+                Expression::Negation(Box::new(Expression::binary(
+                    Expression::Old,
+                    Operator::Subtraction,
+                    Expression::Value(3)
+                ))),
+                Operator::Modulo,
+                Expression::Value(7)
+            )
+        ))
+    );
+    // Left associativity
+    assert_eq!(
+        parse_expression("old - 1 - 2"),
+        Ok((
+            "",
+            Expression::binary(
+                Expression::binary(Expression::Old, Operator::Subtraction, Expression::Value(1)),
+                Operator::Subtraction,
+                Expression::Value(2)
+            )
+        ))
+    );
+}
+
 #[test]
 pub fn test_parse_test_lines() {
     let to_parse: &'static str = "  Test: divisible by 17
@@ -89,9 +140,11 @@ pub fn test_parse_monkey() {
         number: 0,
         items: vec![79, 98].into(),
         operation: Operation {
-            left: Operand::Old,
-            operator: Operator::Multiplication,
-            right: Operand::Value(19),
+            expression: Expression::binary(
+                Expression::Old,
+                Operator::Multiplication,
+                Expression::Value(19),
+            ),
         },
         test: Test {
             quotient: 23,
@@ -100,4 +153,46 @@ pub fn test_parse_monkey() {
         },
         inspected_items: 0,
     })))
-}
\ No newline at end of file
+}
+
+static INPUT_EXAMPLE: &str = include_str!("../input_example");
+
+#[test]
+pub fn test_format_monkeys() {
+    assert_eq!(format_monkeys(&parse_data(INPUT_EXAMPLE)), INPUT_EXAMPLE);
+}
+
+#[test]
+pub fn test_format_empty_items() {
+    let to_parse: &'static str = "Monkey 4:
+  Starting items:
+  Operation: new = old * -2
+  Test: divisible by 5
+    If true: throw to monkey 0
+    If false: throw to monkey 1
+";
+    let (_, monkey) = parse_monkey(to_parse).unwrap();
+    assert!(monkey.items.is_empty());
+    assert_eq!(format!("{}\n", monkey), to_parse);
+}
+
+#[test]
+pub fn test_format_expression() {
+    // Written back exactly as parsed
+    for expression in [
+        "old + 2 * old",
+        "(old + 2) * old",
+        "-(old - 3) % 7",
+        "old - 1 - 2",
+        "old - (1 - 2)",
+        "old / (old * 3)",
+        "--old",
+        "old - -5",
+    ] {
+        let (_, parsed) = parse_expression(expression).unwrap();
+        assert_eq!(parsed.to_string(), expression);
+    }
+    // Redundant parentheses are dropped
+    let (_, parsed) = parse_expression("((old) * (2)) + (old % 3)").unwrap();
+    assert_eq!(parsed.to_string(), "old * 2 + old % 3");
+}
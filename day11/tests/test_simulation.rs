@@ -0,0 +1,289 @@
+use day11::model::EvaluationError;
+use day11::parser::parse_data;
+use day11::simulation::{Cycle, Hop, Reduction, Relief, Simulation, SimulationError};
+use day11::utils::format_monkeys;
+
+static INPUT_EXAMPLE: &str = include_str!("../input_example");
+
+static SMALL_TESTS: &str = "Monkey 0:
+  Starting items: 1, 2, 3
+  Operation: new = old * 5 + 1
+  Test: divisible by 2
+    If true: throw to monkey 1
+    If false: throw to monkey 2
+
+Monkey 1:
+  Starting items: 4
+  Operation: new = old * old
+  Test: divisible by 3
+    If true: throw to monkey 0
+    If false: throw to monkey 2
+
+Monkey 2:
+  Starting items: 5, 7
+  Operation: new = old + 2
+  Test: divisible by 5
+    If true: throw to monkey 1
+    If false: throw to monkey 0
+";
+
+#[test]
+fn test_puzzle_parts() {
+    let mut part_one = Simulation::builder(parse_data(INPUT_EXAMPLE))
+        .build()
+        .unwrap();
+    part_one.run().unwrap();
+    assert_eq!(part_one.inspections(), &[101, 95, 7, 105]);
+    assert_eq!(part_one.monkey_business(), 10605);
+
+    let mut part_two = Simulation::builder(parse_data(INPUT_EXAMPLE))
+        .rounds(10_000)
+        .relief(Relief::None)
+        .reduction(Reduction::Lcm)
+        .build()
+        .unwrap();
+    part_two.run().unwrap();
+    assert_eq!(part_two.inspections(), &[52166, 47830, 1938, 52013]);
+    assert_eq!(part_two.monkey_business(), 2713310158);
+}
+
+#[test]
+fn test_round_inspections() {
+    let mut simulation = Simulation::builder(parse_data(INPUT_EXAMPLE))
+        .rounds(2)
+        .record_rounds(true)
+        .build()
+        .unwrap();
+    simulation.run().unwrap();
+
+    assert_eq!(simulation.round_inspections(1), Some(&[2, 4, 3, 5][..]));
+    assert_eq!(simulation.round_inspections(2), Some(&[4, 6, 1, 5][..]));
+    assert_eq!(simulation.round_inspections(3), None);
+
+    // Only the totals are kept when the rounds are not recorded
+    let mut simulation = Simulation::builder(parse_data(INPUT_EXAMPLE))
+        .rounds(2)
+        .build()
+        .unwrap();
+    simulation.run().unwrap();
+
+    assert_eq!(simulation.simulated_rounds(), 2);
+    assert_eq!(simulation.round_inspections(1), None);
+    assert_eq!(simulation.inspections(), &[6, 10, 4, 10]);
+}
+
+#[test]
+fn test_trajectory() {
+    let mut simulation = Simulation::builder(parse_data(INPUT_EXAMPLE))
+        .rounds(1)
+        .relief(Relief::Custom(|worry_level| worry_level / 3))
+        .track_items(true)
+        .build()
+        .unwrap();
+    simulation.run().unwrap();
+
+    assert_eq!(simulation.item_count(), 10);
+    assert_eq!(
+        simulation.trajectory(0).unwrap(),
+        &[
+            Hop {
+                round: 0,
+                monkey: 0,
+                worry_level: 79
+            },
+            Hop {
+                round: 1,
+                monkey: 3,
+                worry_level: 500
+            },
+            Hop {
+                round: 1,
+                monkey: 1,
+                worry_level: 167
+            },
+        ]
+    );
+    // The last item of monkey 3 is only inspected by monkey 3 during the first round
+    assert_eq!(simulation.trajectory(9).unwrap().len(), 2);
+    assert_eq!(simulation.trajectory(10), None);
+}
+
+#[test]
+fn test_cycle_fast_forward() {
+    let build = |detect_cycles: bool| {
+        Simulation::builder(parse_data(SMALL_TESTS))
+            .rounds(1000)
+            .relief(Relief::None)
+            .reduction(Reduction::Modulo(60))
+            .record_rounds(true)
+            .detect_cycles(detect_cycles)
+            .build()
+            .unwrap()
+    };
+
+    let mut simulated = build(false);
+    simulated.run().unwrap();
+    let mut fast = build(true);
+    fast.run().unwrap();
+
+    let cycle = fast.cycle().unwrap();
+    assert!(cycle.start + cycle.length < 1000);
+    assert_eq!(fast.round(), 1000);
+    assert_eq!(fast.inspections(), simulated.inspections());
+    assert_eq!(fast.monkeys(), simulated.monkeys());
+    for round in 1..=1000 {
+        assert_eq!(
+            fast.round_inspections(round),
+            simulated.round_inspections(round)
+        );
+        assert_eq!(fast.round_stats(round), simulated.round_stats(round));
+    }
+    assert_eq!(fast.throws(), simulated.throws());
+}
+
+#[test]
+fn test_history_limit() {
+    let build = |history_limit: usize| {
+        Simulation::builder(parse_data(SMALL_TESTS))
+            .rounds(1000)
+            .relief(Relief::None)
+            .reduction(Reduction::Modulo(60))
+            .detect_cycles(true)
+            .history_limit(history_limit)
+            .build()
+            .unwrap()
+    };
+
+    let mut detected = build(1000);
+    detected.run().unwrap();
+    let cycle = detected.cycle().unwrap();
+
+    // The detection gives up before the cycle completes, every round is simulated
+    let mut simulated = build(cycle.start + cycle.length - 1);
+    simulated.run().unwrap();
+    assert_eq!(simulated.cycle(), None);
+    assert_eq!(simulated.simulated_rounds(), 1000);
+    assert_eq!(simulated.round_stats(1), None);
+    assert_eq!(simulated.inspections(), detected.inspections());
+    assert_eq!(simulated.monkeys(), detected.monkeys());
+}
+
+#[test]
+fn test_large_round_count() {
+    let mut simulation = Simulation::builder(parse_data(SMALL_TESTS))
+        .rounds(1_000_000_000_000)
+        .relief(Relief::None)
+        .reduction(Reduction::Lcm)
+        .detect_cycles(true)
+        .build()
+        .unwrap();
+    simulation.run().unwrap();
+
+    let Cycle { length, .. } = simulation.cycle().unwrap();
+    assert!(length > 0);
+    // Every item is inspected at least once per round
+    assert!(simulation.inspections().iter().sum::<usize>() >= 6_000_000_000_000);
+}
+
+#[test]
+fn test_build_errors() {
+    assert_eq!(
+        Simulation::builder(parse_data(INPUT_EXAMPLE))
+            .relief(Relief::None)
+            .reduction(Reduction::Modulo(23 * 19 * 13))
+            .build()
+            .err(),
+        Some(SimulationError::IncompatibleModulus {
+            monkey: 3,
+            quotient: 17
+        })
+    );
+    assert_eq!(
+        Simulation::builder(parse_data(INPUT_EXAMPLE))
+            .reduction(Reduction::Lcm)
+            .build()
+            .err(),
+        Some(SimulationError::ReliefWithReduction)
+    );
+    assert!(matches!(
+        Simulation::builder(parse_data(&INPUT_EXAMPLE.replace("old + 6", "old / 6")))
+            .relief(Relief::None)
+            .reduction(Reduction::Lcm)
+            .build()
+            .err(),
+        Some(SimulationError::UnsoundOperation(_))
+    ));
+    assert_eq!(
+        Simulation::builder(parse_data(INPUT_EXAMPLE))
+            .relief(Relief::DivideBy(0))
+            .build()
+            .err(),
+        Some(SimulationError::InvalidDivisor(0))
+    );
+    assert_eq!(
+        Simulation::builder(parse_data(INPUT_EXAMPLE))
+            .relief(Relief::None)
+            .reduction(Reduction::Modulo(0))
+            .build()
+            .err(),
+        Some(SimulationError::InvalidModulus(0))
+    );
+    assert_eq!(
+        Simulation::builder(parse_data(INPUT_EXAMPLE))
+            .relief(Relief::None)
+            .reduction(Reduction::Modulo(-96577))
+            .build()
+            .err(),
+        Some(SimulationError::InvalidModulus(-96577))
+    );
+}
+
+#[test]
+fn test_dump_state() {
+    let mut simulation = Simulation::builder(parse_data(INPUT_EXAMPLE))
+        .rounds(10)
+        .build()
+        .unwrap();
+    simulation.run().unwrap();
+
+    // The dump is a fresh input: resuming from it gives the same items as playing on
+    let dump = format_monkeys(&simulation.monkeys());
+    let mut resumed = Simulation::builder(parse_data(&dump))
+        .rounds(10)
+        .build()
+        .unwrap();
+    resumed.run().unwrap();
+    let mut played = Simulation::builder(parse_data(INPUT_EXAMPLE))
+        .build()
+        .unwrap();
+    played.run().unwrap();
+
+    let items = |simulation: &Simulation| -> Vec<_> {
+        simulation
+            .monkeys()
+            .iter()
+            .map(|monkey| monkey.items.clone())
+            .collect()
+    };
+    assert_eq!(items(&resumed), items(&played));
+}
+
+#[test]
+fn test_overflow() {
+    // Without relief nor reduction, the worry levels of monkey 2 are squared at every round
+    let mut simulation = Simulation::builder(parse_data(INPUT_EXAMPLE))
+        .rounds(1000)
+        .relief(Relief::None)
+        .build()
+        .unwrap();
+
+    assert!(matches!(
+        simulation.run(),
+        Err(SimulationError::Evaluation {
+            monkey: 2,
+            error: EvaluationError::Overflow,
+            ..
+        })
+    ));
+    assert_eq!(simulation.round(), 10);
+}
@@ -0,0 +1,79 @@
+use crate::grid::{GridWithBorder, Point};
+use std::collections::VecDeque;
+
+/// Number of steps from every point of the grid to the end, computed with a single breadth-first
+/// search walking the climbing rules backward from the end
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct DistanceField {
+    end: Point,
+    /// Distance to the end, `None` if the end cannot be reached
+    distances: Vec<Vec<Option<usize>>>,
+    /// Next point on a shortest path to the end
+    next: Vec<Vec<Option<Point>>>,
+}
+
+impl DistanceField {
+    pub fn new(grid: &GridWithBorder) -> DistanceField {
+        let end = grid.end().clone();
+        let mut distances = vec![vec![None; grid.width()]; grid.height()];
+        let mut next = vec![vec![None; grid.width()]; grid.height()];
+
+        distances[end.line][end.column] = Some(0);
+        let mut queue = VecDeque::from([end.clone()]);
+        while let Some(point) = queue.pop_front() {
+            let distance = distances[point.line][point.column].unwrap();
+
+            for predecessor in grid.get_predecessors(point.line, point.column) {
+                if distances[predecessor.line][predecessor.column].is_none() {
+                    distances[predecessor.line][predecessor.column] = Some(distance + 1);
+                    next[predecessor.line][predecessor.column] = Some(point.clone());
+                    queue.push_back(predecessor);
+                }
+            }
+        }
+
+        DistanceField {
+            end,
+            distances,
+            next,
+        }
+    }
+
+    /// Number of steps of the shortest path from the point to the end
+    pub fn distance_from(&self, point: &Point) -> Option<usize> {
+        self.distances
+            .get(point.line)
+            .and_then(|line| line.get(point.column))
+            .copied()
+            .flatten()
+    }
+
+    /// Shortest path from the point to the end, both included
+    pub fn path_from(&self, point: &Point) -> Option<Vec<Point>> {
+        self.distance_from(point)?;
+
+        let mut path = vec![point.clone()];
+        let mut current = point;
+        while let Some(next) = &self.next[current.line][current.column] {
+            path.push(next.clone());
+            current = next;
+        }
+        debug_assert_eq!(path.last(), Some(&self.end));
+
+        Some(path)
+    }
+
+    /// Closest point to the end among the candidates, with its shortest path
+    pub fn closest<'a, I>(&self, candidates: I) -> Option<(Point, Vec<Point>)>
+    where
+        I: IntoIterator<Item = &'a Point>,
+    {
+        let closest = candidates
+            .into_iter()
+            .filter_map(|point| self.distance_from(point).map(|distance| (distance, point)))
+            .min_by_key(|(distance, _)| *distance)?
+            .1;
+
+        Some((closest.clone(), self.path_from(closest).unwrap()))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::rules::ClimbingRules;
 use colored::Colorize;
 use std::collections::HashSet;
 
@@ -87,47 +88,74 @@ impl GridWithBorder {
     }
 
     pub fn get_neighbors(&self, line: usize, column: usize) -> Vec<Point> {
+        self.get_neighbors_with_rules(line, column, &ClimbingRules::default())
+    }
+
+    /// Points that can be reached in one step under the given rules
+    pub fn get_neighbors_with_rules(
+        &self,
+        line: usize,
+        column: usize,
+        rules: &ClimbingRules,
+    ) -> Vec<Point> {
         // Compute coordinates to take borders into account
         let line = line + 1;
         let column = column + 1;
 
-        let mut res = vec![];
-
-        // get current value
         // val is never equal to u8::MAX because we are only looking for points inside the grid and
         // not on the border.
         let val = self.data[line][column];
 
-        // check top neighbor
-        if self.data[line - 1][column] <= val + 1 {
-            res.push(Point {
-                line: line - 2,
-                column: column - 1,
-            })
+        let mut offsets = vec![(-1, 0), (1, 0), (0, -1), (0, 1)];
+        if rules.diagonals {
+            offsets.extend([(-1, -1), (-1, 1), (1, -1), (1, 1)]);
         }
-        // check bottom neighbor
-        if self.data[line + 1][column] <= val + 1 {
-            res.push(Point {
-                line,
-                column: column - 1,
+
+        offsets
+            .into_iter()
+            .map(|(line_offset, column_offset): (isize, isize)| {
+                (
+                    line.wrapping_add_signed(line_offset),
+                    column.wrapping_add_signed(column_offset),
+                )
             })
-        }
-        // check left neighbor
-        if self.data[line][column - 1] <= val + 1 {
-            res.push(Point {
-                line: line - 1,
-                column: column - 2,
+            .filter(|(neighbor_line, neighbor_column)| {
+                let neighbor = self.data[*neighbor_line][*neighbor_column];
+                neighbor != u8::MAX && rules.allows(val, neighbor)
             })
-        }
-        // check right neighbor
-        if self.data[line][column + 1] <= val + 1 {
-            res.push(Point {
-                line: line - 1,
-                column,
+            .map(|(neighbor_line, neighbor_column)| Point {
+                line: neighbor_line - 1,
+                column: neighbor_column - 1,
             })
-        }
+            .collect()
+    }
+
+    /// Points from which the given point can be reached in one step, the reverse of
+    /// `get_neighbors`
+    pub fn get_predecessors(&self, line: usize, column: usize) -> Vec<Point> {
+        // Compute coordinates to take borders into account
+        let line = line + 1;
+        let column = column + 1;
+
+        let val = self.data[line][column];
 
-        res
+        // The border is never a predecessor, and the neighbors can be at most one below
+        [
+            (line - 1, column),
+            (line + 1, column),
+            (line, column - 1),
+            (line, column + 1),
+        ]
+        .into_iter()
+        .filter(|(neighbor_line, neighbor_column)| {
+            let neighbor = self.data[*neighbor_line][*neighbor_column];
+            neighbor != u8::MAX && val <= neighbor + 1
+        })
+        .map(|(neighbor_line, neighbor_column)| Point {
+            line: neighbor_line - 1,
+            column: neighbor_column - 1,
+        })
+        .collect()
     }
 
     pub fn get_a_elevation_list(&self) -> Vec<Point> {
@@ -1 +1,4 @@
-pub mod grid;
\ No newline at end of file
+pub mod grid;
+pub mod distance;
+pub mod rules;
+pub mod search;
\ No newline at end of file
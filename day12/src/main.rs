@@ -1,7 +1,8 @@
 use clap::Parser;
-use day12::grid::{GridWithBorder, Point};
-use petgraph::algo::astar;
-use petgraph::Graph;
+use day12::distance::DistanceField;
+use day12::grid::GridWithBorder;
+use day12::rules::{ClimbingRules, Cost};
+use day12::search::Terrain;
 use std::fs;
 use std::path::PathBuf;
 
@@ -11,11 +12,83 @@ struct Cli {
     #[arg(short, long, default_value_t = false)]
     verbose: bool,
 
+    /// Maximal elevation gain of a move under custom rules, `any` for no limit
+    #[arg(long, value_parser = parse_limit)]
+    max_climb: Option<Limit>,
+
+    /// Maximal elevation loss of a move under custom rules, `any` for no limit
+    #[arg(long, value_parser = parse_limit)]
+    max_descent: Option<Limit>,
+
+    /// Allow diagonal moves under custom rules
+    #[arg(short, long, default_value_t = false)]
+    diagonals: bool,
+
+    /// Cost of every move under custom rules
+    #[arg(long, default_value_t = 1)]
+    step_cost: usize,
+
+    /// Additional cost per level climbed under custom rules
+    #[arg(long, default_value_t = 0)]
+    climb_cost: usize,
+
+    /// Additional cost per level descended under custom rules
+    #[arg(long, default_value_t = 0)]
+    descent_cost: usize,
+
     /// File to parse
     #[arg(default_value = "input")]
     path: PathBuf,
 }
 
+/// Limit of the elevation change of a move, parsed from the command line
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+struct Limit(Option<u8>);
+
+fn parse_limit(value: &str) -> Result<Limit, String> {
+    match value {
+        "any" => Ok(Limit(None)),
+        limit => limit
+            .parse()
+            .map(|limit| Limit(Some(limit)))
+            .map_err(|_| format!("expected any or a number of levels, got {}", value)),
+    }
+}
+
+fn solve_custom(args: &Cli, grid: &GridWithBorder) {
+    let mut rules = ClimbingRules {
+        diagonals: args.diagonals,
+        cost: Cost::Linear {
+            step: args.step_cost,
+            climb: args.climb_cost,
+            descent: args.descent_cost,
+        },
+        ..ClimbingRules::default()
+    };
+    if let Some(Limit(limit)) = args.max_climb {
+        rules.max_climb = limit;
+    }
+    if let Some(Limit(limit)) = args.max_descent {
+        rules.max_descent = limit;
+    }
+
+    let terrain = Terrain::new(grid, rules);
+    match terrain.shortest_path(grid.start(), grid.end()) {
+        Some((cost, path)) => {
+            if args.verbose {
+                println!("Heuristic: {:?}", rules.heuristic());
+                grid.pretty_print_path(&path);
+            }
+            println!(
+                "Custom rules solution: {:#?} ({} moves)",
+                cost,
+                path.len() - 1
+            );
+        }
+        None => println!("Custom rules solution: the end cannot be reached"),
+    }
+}
+
 fn main() {
     let args = Cli::parse();
 
@@ -26,69 +99,35 @@ fn main() {
         grid.pretty_print();
     }
 
-    // Create a graph from the grid
-    let mut graph: Graph<Point, usize> = Graph::new();
-
-    // Create graph nodes from the grid
-    let nodes: Vec<Vec<_>> = (0..grid.height())
-        .into_iter()
-        .map(|line| {
-            (0..grid.width())
-                .into_iter()
-                .map(|column| graph.add_node(Point { line, column }))
-                .collect()
-        })
-        .collect();
-
-    // Create graph edges if possible
-    for line in 0..grid.height() {
-        for column in 0..grid.width() {
-            for neighbor in grid.get_neighbors(line, column) {
-                graph.add_edge(
-                    nodes[line][column],
-                    nodes[neighbor.line][neighbor.column],
-                    1,
-                );
-            }
-        }
+    // A single search from the end gives the distance from every point
+    let field = DistanceField::new(&grid);
+
+    let path = field.path_from(grid.start()).unwrap();
+    if args.verbose {
+        grid.pretty_print_path(&path);
     }
 
-    let start = nodes[grid.start().line][grid.start().column];
-    let end = nodes[grid.end().line][grid.end().column];
-
-    let (len, path) = astar(
-        &graph,
-        start,
-        |finish| finish == end,
-        |e| *e.weight(),
-        |_| 0,
-    )
-    .unwrap();
-    let path: Vec<_> = path
-        .into_iter()
-        .map(|node_index| graph.node_weight(node_index).unwrap().clone())
-        .collect();
+    println!(
+        "Part one solution: {:#?}",
+        field.distance_from(grid.start()).unwrap()
+    );
 
+    // The closest point at elevation a
+    let (start, path) = field.closest(&grid.get_a_elevation_list()).unwrap();
     if args.verbose {
+        println!("Best starting point: {:?}", start);
         grid.pretty_print_path(&path);
     }
 
-    println!("Part one solution: {:#?}", len);
-
-    // For every point of the graph that is at elevation a
-    // This is not an optimal answer, we should instead build a graph that starts from the end
-    // and has any point with the elevation a as a possible end
-    let to_test = grid.get_a_elevation_list();
-    let res = to_test.into_iter().filter_map(|to_test| {
-        let start = nodes[to_test.line][to_test.column];
-        astar(
-            &graph,
-            start,
-            |finish| finish == end,
-            |e| *e.weight(),
-            |_| 0,
-        )
-    }).map(|(len, _)| len).min().unwrap();
-    println!("Part two solution: {:#?}", res);
+    println!("Part two solution: {:#?}", path.len() - 1);
 
+    if args.max_climb.is_some()
+        || args.max_descent.is_some()
+        || args.diagonals
+        || args.step_cost != 1
+        || args.climb_cost != 0
+        || args.descent_cost != 0
+    {
+        solve_custom(&args, &grid);
+    }
 }
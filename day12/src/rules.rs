@@ -0,0 +1,111 @@
+use crate::grid::Point;
+
+/// Cost of a move, as a function of the elevation change
+#[derive(Debug, Clone, Copy)]
+pub enum Cost {
+    /// Every move costs 1, as in the puzzle
+    Unit,
+    /// Every move costs `step`, plus `climb` per level climbed and `descent` per level descended
+    Linear {
+        step: usize,
+        climb: usize,
+        descent: usize,
+    },
+    Custom(fn(i32) -> usize),
+}
+
+impl Cost {
+    pub fn apply(&self, elevation_change: i32) -> usize {
+        match self {
+            Self::Unit => 1,
+            Self::Linear {
+                step,
+                climb,
+                descent,
+            } => {
+                step + climb * elevation_change.max(0) as usize
+                    + descent * (-elevation_change).max(0) as usize
+            }
+            Self::Custom(cost) => cost(elevation_change),
+        }
+    }
+}
+
+/// Distance between two points, never greater than the number of moves needed to go from one to
+/// the other
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Heuristic {
+    /// For moves to the 4 neighbors
+    Manhattan,
+    /// For moves to the 8 neighbors
+    Chebyshev,
+}
+
+impl Heuristic {
+    pub fn distance(&self, from: &Point, to: &Point) -> usize {
+        let lines = from.line.abs_diff(to.line);
+        let columns = from.column.abs_diff(to.column);
+        match self {
+            Self::Manhattan => lines + columns,
+            Self::Chebyshev => lines.max(columns),
+        }
+    }
+}
+
+/// How the hiker is allowed to move and what it costs
+#[derive(Debug, Clone, Copy)]
+pub struct ClimbingRules {
+    /// Maximal elevation gain of a move, `None` for no limit
+    pub max_climb: Option<u8>,
+    /// Maximal elevation loss of a move, `None` for no limit
+    pub max_descent: Option<u8>,
+    /// Allow moves to the diagonal neighbors
+    pub diagonals: bool,
+    pub cost: Cost,
+}
+
+/// The rules of the puzzle: climb at most 1, descend any amount, move to the 4 neighbors
+impl Default for ClimbingRules {
+    fn default() -> Self {
+        ClimbingRules {
+            max_climb: Some(1),
+            max_descent: None,
+            diagonals: false,
+            cost: Cost::Unit,
+        }
+    }
+}
+
+impl ClimbingRules {
+    pub fn allows(&self, from: u8, to: u8) -> bool {
+        self.allows_change(to as i32 - from as i32)
+    }
+
+    fn allows_change(&self, change: i32) -> bool {
+        self.max_climb.is_none_or(|max| change <= max as i32)
+            && self.max_descent.is_none_or(|max| -change <= max as i32)
+    }
+
+    pub fn heuristic(&self) -> Heuristic {
+        if self.diagonals {
+            Heuristic::Chebyshev
+        } else {
+            Heuristic::Manhattan
+        }
+    }
+
+    /// Lowest cost of a single allowed move between two elevations
+    pub fn min_move_cost(&self) -> usize {
+        (-25..=25)
+            .filter(|change| self.allows_change(*change))
+            .map(|change| self.cost.apply(change))
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Lower bound of the cost of going from one point to another, so that the A* search stays
+    /// optimal
+    pub fn estimate(&self, from: &Point, to: &Point) -> usize {
+        self.heuristic().distance(from, to) * self.min_move_cost()
+    }
+}
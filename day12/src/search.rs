@@ -0,0 +1,72 @@
+use crate::grid::{GridWithBorder, Point};
+use crate::rules::ClimbingRules;
+use petgraph::algo::astar;
+use petgraph::graph::NodeIndex;
+use petgraph::Graph;
+
+/// Graph of the allowed moves of a grid under some climbing rules, weighted by their cost
+pub struct Terrain {
+    rules: ClimbingRules,
+    graph: Graph<Point, usize>,
+    nodes: Vec<Vec<NodeIndex>>,
+}
+
+impl Terrain {
+    pub fn new(grid: &GridWithBorder, rules: ClimbingRules) -> Terrain {
+        let mut graph: Graph<Point, usize> = Graph::new();
+
+        // Create graph nodes from the grid
+        let nodes: Vec<Vec<_>> = (0..grid.height())
+            .map(|line| {
+                (0..grid.width())
+                    .map(|column| graph.add_node(Point { line, column }))
+                    .collect()
+            })
+            .collect();
+
+        // Create graph edges if possible
+        for line in 0..grid.height() {
+            for column in 0..grid.width() {
+                let elevation = grid.get(line, column) as i32;
+                for neighbor in grid.get_neighbors_with_rules(line, column, &rules) {
+                    let change = grid.get(neighbor.line, neighbor.column) as i32 - elevation;
+                    graph.add_edge(
+                        nodes[line][column],
+                        nodes[neighbor.line][neighbor.column],
+                        rules.cost.apply(change),
+                    );
+                }
+            }
+        }
+
+        Terrain {
+            rules,
+            graph,
+            nodes,
+        }
+    }
+
+    pub fn rules(&self) -> &ClimbingRules {
+        &self.rules
+    }
+
+    /// Cheapest path between two points, with its cost
+    pub fn shortest_path(&self, from: &Point, to: &Point) -> Option<(usize, Vec<Point>)> {
+        let start = self.nodes[from.line][from.column];
+        let end = self.nodes[to.line][to.column];
+
+        let (cost, path) = astar(
+            &self.graph,
+            start,
+            |finish| finish == end,
+            |e| *e.weight(),
+            |node| self.rules.estimate(&self.graph[node], to),
+        )?;
+        let path = path
+            .into_iter()
+            .map(|node_index| self.graph[node_index].clone())
+            .collect();
+
+        Some((cost, path))
+    }
+}
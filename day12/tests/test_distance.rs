@@ -0,0 +1,76 @@
+use day12::distance::DistanceField;
+use day12::grid::{GridWithBorder, Point};
+
+static INPUT_EXAMPLE: &str = include_str!("../input_example");
+
+#[test]
+fn test_get_predecessors() {
+    let grid = GridWithBorder::from_str(INPUT_EXAMPLE);
+
+    // The predecessors are exactly the points having this point as a neighbor
+    for line in 0..grid.height() {
+        for column in 0..grid.width() {
+            let point = Point { line, column };
+            for predecessor in grid.get_predecessors(line, column) {
+                assert!(grid
+                    .get_neighbors(predecessor.line, predecessor.column)
+                    .contains(&point));
+            }
+            for neighbor in grid.get_neighbors(line, column) {
+                assert!(grid
+                    .get_predecessors(neighbor.line, neighbor.column)
+                    .contains(&point));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_distance_from() {
+    let grid = GridWithBorder::from_str(INPUT_EXAMPLE);
+    let field = DistanceField::new(&grid);
+
+    assert_eq!(field.distance_from(grid.start()), Some(31));
+    assert_eq!(field.distance_from(grid.end()), Some(0));
+    // Next to the end, but one step higher than the end
+    assert_eq!(field.distance_from(&Point { line: 2, column: 4 }), Some(1));
+    assert_eq!(field.distance_from(&Point { line: 5, column: 0 }), None);
+}
+
+#[test]
+fn test_path_from() {
+    let grid = GridWithBorder::from_str(INPUT_EXAMPLE);
+    let field = DistanceField::new(&grid);
+
+    let path = field.path_from(grid.start()).unwrap();
+    assert_eq!(path.len(), 32);
+    assert_eq!(&path[0], grid.start());
+    assert_eq!(&path[31], grid.end());
+    for step in path.windows(2) {
+        assert!(grid
+            .get_neighbors(step[0].line, step[0].column)
+            .contains(&step[1]));
+    }
+}
+
+#[test]
+fn test_unreachable() {
+    // The end is behind a cliff
+    let grid = GridWithBorder::from_str("SbcdE\nzzzzz\n");
+    let field = DistanceField::new(&grid);
+
+    assert_eq!(field.distance_from(grid.start()), None);
+    assert_eq!(field.path_from(grid.start()), None);
+    assert_eq!(field.closest(&grid.get_a_elevation_list()), None);
+}
+
+#[test]
+fn test_closest() {
+    let grid = GridWithBorder::from_str(INPUT_EXAMPLE);
+    let field = DistanceField::new(&grid);
+
+    let (start, path) = field.closest(&grid.get_a_elevation_list()).unwrap();
+    assert_eq!(path.len() - 1, 29);
+    assert_eq!(grid.get(start.line, start.column), 0);
+    assert_eq!(path[0], start);
+}
@@ -0,0 +1,203 @@
+use day12::distance::DistanceField;
+use day12::grid::{GridWithBorder, Point};
+use day12::rules::{ClimbingRules, Cost, Heuristic};
+use day12::search::Terrain;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+static INPUT_EXAMPLE: &str = include_str!("../input_example");
+
+/// Cost of the cheapest path without any heuristic
+fn dijkstra(grid: &GridWithBorder, rules: &ClimbingRules) -> Option<usize> {
+    let mut costs = vec![vec![usize::MAX; grid.width()]; grid.height()];
+    let mut queue = BinaryHeap::from([Reverse((0, grid.start().line, grid.start().column))]);
+
+    while let Some(Reverse((cost, line, column))) = queue.pop() {
+        if cost >= costs[line][column] {
+            continue;
+        }
+        costs[line][column] = cost;
+
+        for neighbor in grid.get_neighbors_with_rules(line, column, rules) {
+            let change =
+                grid.get(neighbor.line, neighbor.column) as i32 - grid.get(line, column) as i32;
+            queue.push(Reverse((
+                cost + rules.cost.apply(change),
+                neighbor.line,
+                neighbor.column,
+            )));
+        }
+    }
+
+    Some(costs[grid.end().line][grid.end().column]).filter(|cost| *cost != usize::MAX)
+}
+
+fn check_path(grid: &GridWithBorder, rules: &ClimbingRules, cost: usize, path: &[Point]) {
+    assert_eq!(&path[0], grid.start());
+    assert_eq!(path.last(), Some(grid.end()));
+
+    let mut total = 0;
+    for step in path.windows(2) {
+        assert!(grid
+            .get_neighbors_with_rules(step[0].line, step[0].column, rules)
+            .contains(&step[1]));
+        total += rules.cost.apply(
+            grid.get(step[1].line, step[1].column) as i32
+                - grid.get(step[0].line, step[0].column) as i32,
+        );
+    }
+    assert_eq!(total, cost);
+}
+
+#[test]
+fn test_default_rules() {
+    let grid = GridWithBorder::from_str(INPUT_EXAMPLE);
+    let rules = ClimbingRules::default();
+    let terrain = Terrain::new(&grid, rules);
+
+    let (cost, path) = terrain.shortest_path(grid.start(), grid.end()).unwrap();
+    assert_eq!(cost, 31);
+    assert_eq!(
+        Some(cost),
+        DistanceField::new(&grid).distance_from(grid.start())
+    );
+    check_path(&grid, &rules, cost, &path);
+}
+
+#[test]
+fn test_optimal_under_rules() {
+    let grid = GridWithBorder::from_str(INPUT_EXAMPLE);
+    let rule_sets = [
+        ClimbingRules {
+            diagonals: true,
+            ..ClimbingRules::default()
+        },
+        ClimbingRules {
+            cost: Cost::Linear {
+                step: 1,
+                climb: 5,
+                descent: 0,
+            },
+            ..ClimbingRules::default()
+        },
+        ClimbingRules {
+            max_climb: Some(2),
+            max_descent: Some(1),
+            diagonals: true,
+            cost: Cost::Linear {
+                step: 2,
+                climb: 1,
+                descent: 3,
+            },
+        },
+        ClimbingRules {
+            max_climb: None,
+            cost: Cost::Custom(|change| 1 + (change * change) as usize),
+            ..ClimbingRules::default()
+        },
+    ];
+
+    for rules in rule_sets {
+        let terrain = Terrain::new(&grid, rules);
+        let (cost, path) = terrain.shortest_path(grid.start(), grid.end()).unwrap();
+
+        // The heuristic is admissible: A* finds the same cost as a search without it
+        assert_eq!(Some(cost), dijkstra(&grid, &rules));
+        check_path(&grid, &rules, cost, &path);
+    }
+}
+
+#[test]
+fn test_expected_costs() {
+    let grid = GridWithBorder::from_str(INPUT_EXAMPLE);
+    let cost = |rules| {
+        Terrain::new(&grid, rules)
+            .shortest_path(grid.start(), grid.end())
+            .map(|(cost, _)| cost)
+    };
+
+    assert_eq!(
+        cost(ClimbingRules {
+            diagonals: true,
+            ..ClimbingRules::default()
+        }),
+        Some(27)
+    );
+    // Every path climbs the 25 levels
+    assert_eq!(
+        cost(ClimbingRules {
+            cost: Cost::Linear {
+                step: 1,
+                climb: 5,
+                descent: 0,
+            },
+            ..ClimbingRules::default()
+        }),
+        Some(31 + 5 * 25)
+    );
+    assert_eq!(
+        cost(ClimbingRules {
+            max_climb: None,
+            diagonals: true,
+            ..ClimbingRules::default()
+        }),
+        Some(5)
+    );
+    assert_eq!(
+        cost(ClimbingRules {
+            max_climb: Some(0),
+            ..ClimbingRules::default()
+        }),
+        None
+    );
+}
+
+#[test]
+fn test_neighbors_with_rules() {
+    let grid = GridWithBorder::from_str("Sbc\nbcd\ncdE\n");
+
+    let center = ClimbingRules {
+        max_climb: Some(1),
+        max_descent: Some(0),
+        diagonals: true,
+        cost: Cost::Unit,
+    };
+    let mut neighbors = grid.get_neighbors_with_rules(1, 1, &center);
+    neighbors.sort_by_key(|point| (point.line, point.column));
+    assert_eq!(
+        neighbors,
+        vec![
+            Point { line: 0, column: 2 },
+            Point { line: 1, column: 2 },
+            Point { line: 2, column: 0 },
+            Point { line: 2, column: 1 },
+        ]
+    );
+
+    // The corner has no neighbors outside the grid
+    assert_eq!(
+        grid.get_neighbors_with_rules(0, 0, &center),
+        vec![Point { line: 1, column: 0 }, Point { line: 0, column: 1 }]
+    );
+}
+
+#[test]
+fn test_heuristic() {
+    let from = Point { line: 1, column: 7 };
+    let to = Point { line: 4, column: 2 };
+    assert_eq!(Heuristic::Manhattan.distance(&from, &to), 8);
+    assert_eq!(Heuristic::Chebyshev.distance(&from, &to), 5);
+
+    let rules = ClimbingRules {
+        diagonals: true,
+        cost: Cost::Linear {
+            step: 2,
+            climb: 3,
+            descent: 1,
+        },
+        ..ClimbingRules::default()
+    };
+    assert_eq!(rules.heuristic(), Heuristic::Chebyshev);
+    assert_eq!(rules.min_move_cost(), 2);
+    assert_eq!(rules.estimate(&from, &to), 10);
+}